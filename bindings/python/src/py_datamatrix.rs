@@ -45,6 +45,6 @@ impl PyDataMatrix {
     }
 
     pub fn data(&self) -> Vec<Vec<f64>> {
-        self.inner.data().clone()
+        self.inner.to_rows()
     }
 }
@@ -0,0 +1,383 @@
+use std::collections::HashMap;
+
+use crate::packed_datamatrix::{packed_index, packed_len};
+use crate::{DuplicatePolicy, Element, Error};
+
+/// Two-dimensional table that grows on demand.
+///
+/// Dense cells are kept as rows, which may have different lengths; sparse cells are kept in a hash map,
+/// so only cells which have been written take memory.
+struct Grid<T> {
+    cells: Cells<T>,
+    fill: T,
+}
+
+enum Cells<T> {
+    Dense(Vec<Vec<T>>),
+    Sparse(HashMap<(usize, usize), T>),
+}
+
+impl<T: Clone> Grid<T> {
+    fn new(fill: T, sparse: bool) -> Self {
+        let cells = if sparse {
+            Cells::Sparse(HashMap::new())
+        } else {
+            Cells::Dense(Vec::new())
+        };
+        Self { cells, fill }
+    }
+
+    fn get(&self, i: usize, j: usize) -> T {
+        let cell = match &self.cells {
+            Cells::Dense(rows) => rows.get(i).and_then(|row| row.get(j)),
+            Cells::Sparse(cells) => cells.get(&(i, j)),
+        };
+        cell.unwrap_or(&self.fill).clone()
+    }
+
+    /// Returns a reference to the cell (i, j), allocating it first if necessary.
+    fn get_mut(&mut self, i: usize, j: usize) -> &mut T {
+        match &mut self.cells {
+            Cells::Dense(rows) => {
+                if i >= rows.len() {
+                    rows.resize_with(i + 1, Vec::new);
+                }
+                let row = &mut rows[i];
+                if j >= row.len() {
+                    row.resize(j + 1, self.fill.clone());
+                }
+                &mut row[j]
+            }
+            Cells::Sparse(cells) => cells.entry((i, j)).or_insert_with(|| self.fill.clone()),
+        }
+    }
+
+    /// Turns this grid into `nrows` rows, each of `ncols` values.
+    fn into_rows(self, nrows: usize, ncols: usize) -> Vec<Vec<T>> {
+        let mut rows = match self.cells {
+            Cells::Dense(rows) => rows,
+            Cells::Sparse(cells) => {
+                let mut rows = vec![Vec::new(); nrows];
+                for ((i, j), value) in cells {
+                    if rows[i].len() <= j {
+                        rows[i].resize(j + 1, self.fill.clone());
+                    }
+                    rows[i][j] = value;
+                }
+                rows
+            }
+        };
+        rows.resize_with(nrows, Vec::new);
+        for row in rows.iter_mut() {
+            row.resize(ncols, self.fill.clone());
+            row.shrink_to_fit();
+        }
+        rows
+    }
+
+    /// Turns this grid into `(i, j, value)` triples of all its allocated cells, in no particular order.
+    fn into_cells(self) -> Box<dyn Iterator<Item = (usize, usize, T)>>
+    where
+        T: 'static,
+    {
+        match self.cells {
+            Cells::Dense(rows) => Box::new(
+                rows.into_iter()
+                    .enumerate()
+                    .flat_map(|(i, row)| row.into_iter().enumerate().map(move |(j, value)| (i, j, value))),
+            ),
+            Cells::Sparse(cells) => Box::new(cells.into_iter().map(|((i, j), value)| (i, j, value))),
+        }
+    }
+}
+
+/// Marks of the entries given so far.
+///
+/// To report conflicts, the line where each entry was given first is kept instead of a flag; lines are
+/// stored as `u32` to save memory, so a line beyond `u32::MAX` is reported as `u32::MAX`.
+enum Given {
+    Flags(Grid<bool>),
+    /// line where an entry was given first; 0 for an entry not given yet
+    Lines(Grid<u32>),
+}
+
+impl Given {
+    /// Marks the entry (i, j) as given at a line; returns `true` if it has not been given before.
+    fn mark(&mut self, i: usize, j: usize, line: usize) -> bool {
+        match self {
+            Given::Flags(flags) => !std::mem::replace(flags.get_mut(i, j), true),
+            Given::Lines(lines) => {
+                let first = lines.get_mut(i, j);
+                if *first != 0 {
+                    return false;
+                }
+                *first = u32::try_from(line).unwrap_or(u32::MAX).max(1);
+                true
+            }
+        }
+    }
+
+    fn is_given(&self, i: usize, j: usize) -> bool {
+        match self {
+            Given::Flags(flags) => flags.get(i, j),
+            Given::Lines(lines) => lines.get(i, j) != 0,
+        }
+    }
+
+    /// Line where the entry (i, j) was given first, or 0 if unknown.
+    fn first_line(&self, i: usize, j: usize) -> usize {
+        match self {
+            Given::Flags(_) => 0,
+            Given::Lines(lines) => lines.get(i, j) as usize,
+        }
+    }
+
+    /// Turns the marks into `nrows` rows of `ncols` flags.
+    fn into_rows(self, nrows: usize, ncols: usize) -> Vec<Vec<bool>> {
+        match self {
+            Given::Flags(flags) => flags.into_rows(nrows, ncols),
+            Given::Lines(lines) => lines
+                .into_rows(nrows, ncols)
+                .into_iter()
+                .map(|row| row.into_iter().map(|line| line != 0).collect())
+                .collect(),
+        }
+    }
+}
+
+/// Matrix entries collected while an input is parsed.
+///
+/// Entries are addressed by row and column ids, assigned to labels in the order they are first seen.
+/// The storage grows as new labels show up, so the size of a matrix need not be known up front.
+/// Values given more than once for the same entry are resolved according to a [`DuplicatePolicy`].
+pub(crate) struct Accumulator<T> {
+    values: Grid<T>,
+    given: Given,
+    /// how many times an entry was given; tracked only to compute averages
+    counts: Option<Grid<u32>>,
+    policy: DuplicatePolicy,
+    fill: T,
+}
+
+impl<T: Element> Accumulator<T> {
+    pub(crate) fn new(fill: T, policy: DuplicatePolicy) -> Self {
+        Self::with_storage(fill, policy, false)
+    }
+
+    /// Creates an accumulator which takes memory only for the entries actually given.
+    pub(crate) fn sparse(fill: T, policy: DuplicatePolicy) -> Self {
+        Self::with_storage(fill, policy, true)
+    }
+
+    fn with_storage(fill: T, policy: DuplicatePolicy, sparse: bool) -> Self {
+        Self {
+            values: Grid::new(fill.clone(), sparse),
+            given: if policy == DuplicatePolicy::Error {
+                Given::Lines(Grid::new(0, sparse))
+            } else {
+                Given::Flags(Grid::new(false, sparse))
+            },
+            counts: (policy == DuplicatePolicy::Average).then(|| Grid::new(0, sparse)),
+            policy,
+            fill,
+        }
+    }
+
+    /// Adds a value of the entry (i, j) given at a line of the input.
+    ///
+    /// The `labels` closure provides the row and column labels, should a conflict be reported.
+    pub(crate) fn add<F>(&mut self, i: usize, j: usize, value: T, line: usize, labels: F) -> Result<(), Error>
+    where
+        F: FnOnce() -> (String, String),
+    {
+        let value = if self.given.mark(i, j, line) {
+            value
+        } else {
+            let previous = self.values.get(i, j);
+            let resolved = match self.policy {
+                DuplicatePolicy::KeepLast => Some(value),
+                DuplicatePolicy::KeepFirst => Some(previous),
+                DuplicatePolicy::Error if previous == value => Some(value),
+                DuplicatePolicy::Error => {
+                    let (row, col) = labels();
+                    return Err(Error::ConflictingValues {
+                        row,
+                        col,
+                        first_line: self.given.first_line(i, j),
+                        first_value: previous.to_string(),
+                        second_line: line,
+                        second_value: value.to_string(),
+                    });
+                }
+                DuplicatePolicy::Sum => previous.sum(&value),
+                // --- the mean is checked for the sum so far, so that the final one can't fail
+                DuplicatePolicy::Average => {
+                    let count = self.counts.as_ref().map_or(1, |counts| counts.get(i, j)) as usize + 1;
+                    previous.sum(&value).filter(|sum| sum.mean(count).is_some())
+                }
+                DuplicatePolicy::Min => previous.smaller(&value),
+                DuplicatePolicy::Max => previous.larger(&value),
+            };
+            match resolved {
+                Some(value) => value,
+                None => {
+                    let (row, col) = labels();
+                    let (policy, type_name) = (self.policy, std::any::type_name::<T>());
+                    return Err(if supports(policy, &T::default()) {
+                        Error::ValueOverflow {
+                            row,
+                            col,
+                            policy,
+                            type_name,
+                        }
+                    } else {
+                        Error::UnsupportedPolicy {
+                            row,
+                            col,
+                            policy,
+                            type_name,
+                        }
+                    });
+                }
+            }
+        };
+        if let Some(counts) = self.counts.as_mut() {
+            *counts.get_mut(i, j) += 1;
+        }
+        *self.values.get_mut(i, j) = value;
+        Ok(())
+    }
+
+    /// Turns the collected entries into rows of values and flags of missing entries, packed row-wise.
+    ///
+    /// For a `symmetric` matrix only entries (i, j) where `i <= j` are expected; they are mirrored to (j, i).
+    /// When given, `row_order[id]` and `col_order[id]` provide the final positions of row and column ids.
+    pub(crate) fn into_matrix(
+        self,
+        nrows: usize,
+        ncols: usize,
+        symmetric: bool,
+        row_order: Option<&[usize]>,
+        col_order: Option<&[usize]>,
+    ) -> (Vec<T>, Vec<bool>) {
+        let mut values = self.values.into_rows(nrows, ncols);
+        let mut given = self.given.into_rows(nrows, ncols);
+        if let Some(counts) = self.counts {
+            // --- so far values hold sums of duplicated values
+            for (row, counts) in values.iter_mut().zip(counts.into_rows(nrows, ncols)) {
+                for (value, count) in row.iter_mut().zip(counts) {
+                    if count > 1 {
+                        *value = value.mean(count as usize).expect("means are checked while entries are added");
+                    }
+                }
+            }
+        }
+        if symmetric {
+            for i in 1..nrows {
+                for j in 0..i {
+                    values[i][j] = values[j][i].clone();
+                    given[i][j] = given[j][i];
+                }
+            }
+        }
+        if let Some(order) = row_order {
+            values = reorder(values, order, Vec::new());
+            given = reorder(given, order, Vec::new());
+        }
+        // --- rows are packed into a single buffer, permuting their columns on the way
+        let mut data = Vec::with_capacity(nrows * ncols);
+        let mut missing = Vec::with_capacity(nrows * ncols);
+        for (row, given) in values.into_iter().zip(given) {
+            match col_order {
+                Some(order) => {
+                    data.extend(reorder(row, order, self.fill.clone()));
+                    missing.extend(reorder(given, order, false).into_iter().map(|g| !g));
+                }
+                None => {
+                    data.extend(row);
+                    missing.extend(given.into_iter().map(|g| !g));
+                }
+            }
+        }
+
+        (data, missing)
+    }
+
+    /// Turns the collected entries of a symmetric matrix into its upper triangle, packed row-wise,
+    /// and flags of missing entries.
+    ///
+    /// Only entries (i, j) where `i <= j` are expected; diagonal entries are dropped unless `with_diagonal` is set.
+    /// When given, `order[id]` provides the final position of a row (and column) id.
+    pub(crate) fn into_packed(self, n: usize, with_diagonal: bool, order: Option<&[usize]>) -> (Vec<T>, Vec<bool>) {
+        let mut data = vec![self.fill.clone(); packed_len(n, with_diagonal)];
+        let mut missing = vec![true; data.len()];
+        for (i, j, value) in self.into_given() {
+            let (p, q) = order.map_or((i, j), |order| (order[i], order[j]));
+            if let Some(pos) = packed_index(n, p, q, with_diagonal) {
+                data[pos] = value;
+                missing[pos] = false;
+            }
+        }
+
+        (data, missing)
+    }
+
+    /// Turns the collected entries into `(i, j, value)` triples sorted by rows and columns.
+    ///
+    /// For a `symmetric` matrix only entries (i, j) where `i <= j` are expected; they are mirrored to (j, i).
+    /// When given, `row_order[id]` and `col_order[id]` provide the final positions of row and column ids.
+    pub(crate) fn into_triplets(
+        self,
+        symmetric: bool,
+        row_order: Option<&[usize]>,
+        col_order: Option<&[usize]>,
+    ) -> Vec<(usize, usize, T)> {
+        let mut triplets = Vec::new();
+        for (i, j, value) in self.into_given() {
+            let (p, q) = (row_order.map_or(i, |order| order[i]), col_order.map_or(j, |order| order[j]));
+            if symmetric && p != q {
+                triplets.push((q, p, value.clone()));
+            }
+            triplets.push((p, q, value));
+        }
+        triplets.sort_unstable_by_key(|&(i, j, _)| (i, j));
+
+        triplets
+    }
+
+    /// Iterates over the entries which have been given, as `(i, j, value)` triples in no particular order;
+    /// duplicated values are already averaged if required.
+    fn into_given(self) -> impl Iterator<Item = (usize, usize, T)> {
+        let (given, counts) = (self.given, self.counts);
+        self.values.into_cells().filter(move |&(i, j, _)| given.is_given(i, j)).map(move |(i, j, value)| {
+            match counts.as_ref().map(|counts| counts.get(i, j)) {
+                Some(count) if count > 1 => {
+                    let mean = value.mean(count as usize).expect("means are checked while entries are added");
+                    (i, j, mean)
+                }
+                _ => (i, j, value),
+            }
+        })
+    }
+}
+
+/// Checks whether values of a type can be aggregated by a policy at all, by aggregating a sample value;
+/// a policy which is supported can still fail for given values when their sum overflows.
+fn supports<T: Element>(policy: DuplicatePolicy, sample: &T) -> bool {
+    match policy {
+        DuplicatePolicy::Sum => sample.sum(sample).is_some(),
+        DuplicatePolicy::Average => sample.sum(sample).and_then(|sum| sum.mean(2)).is_some(),
+        DuplicatePolicy::Min => sample.smaller(sample).is_some(),
+        DuplicatePolicy::Max => sample.larger(sample).is_some(),
+        _ => true,
+    }
+}
+
+/// Moves each element `items[id]` to the position `order[id]`.
+fn reorder<T: Clone>(items: Vec<T>, order: &[usize], fill: T) -> Vec<T> {
+    let mut reordered = vec![fill; items.len()];
+    for (id, item) in items.into_iter().enumerate() {
+        reordered[order[id]] = item;
+    }
+    reordered
+}
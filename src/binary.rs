@@ -0,0 +1,405 @@
+//! Binary on-disk format of a [`DataMatrix`], which loads without parsing any text.
+//!
+//! A file starts with a header of 64 bytes; integers of the header are little-endian:
+//!
+//! | offset | size | content                                                             |
+//! |-------:|-----:|---------------------------------------------------------------------|
+//! |      0 |    8 | magic bytes `DMATRIX\0`                                             |
+//! |      8 |    2 | format version, currently 1                                         |
+//! |     10 |    1 | byte order of values: 0 for little-endian, 1 for big-endian         |
+//! |     11 |    1 | element type code, see [`BinaryElement::TYPE_CODE`]                 |
+//! |     12 |    1 | flags: bit 0 is set when the file holds a mask of missing entries   |
+//! |     13 |    3 | reserved, zero                                                      |
+//! |     16 |    8 | number of rows                                                      |
+//! |     24 |    8 | number of columns                                                   |
+//! |     32 |    8 | size of the label tables in bytes                                   |
+//! |     40 |    8 | offset of the values from the start of the file                     |
+//! |     48 |   16 | reserved, zero                                                      |
+//!
+//! The header is followed by the label tables: row labels, then column labels, each as a 32-bit
+//! little-endian length followed by that many bytes of UTF-8 text. Zero bytes pad the tables up to
+//! the offset of the values, a multiple of 64, so values are aligned in memory when the file is mapped.
+//!
+//! Values are stored row-major, `nrows * ncols` of them, in the byte order given by the header,
+//! i.e. the native order of the machine which wrote the file. The optional mask of missing entries
+//! comes last: one bit per entry, least significant bit first, in the same order as the values.
+//!
+//! Readers must reject files of a version they don't know.
+use std::io::{self, BufRead, Read, Write};
+use std::path::Path;
+
+use crate::io_utils::{open_file, write_file};
+use crate::{DataMatrix, DataMatrixBuilder, DataMatrixWriter, Element, Error};
+
+#[cfg(feature = "mmap")]
+mod mapped;
+#[cfg(feature = "mmap")]
+pub use mapped::MappedDataMatrix;
+
+/// Magic bytes a binary matrix file starts with
+const MAGIC: &[u8; 8] = b"DMATRIX\0";
+/// Version of the format written by this crate
+pub(crate) const VERSION: u16 = 1;
+/// Size of the header in bytes
+const HEADER_SIZE: usize = 64;
+/// Values start at an offset which is a multiple of this
+const DATA_ALIGNMENT: u64 = 64;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Types of values which can be stored in the [binary format](crate::binary).
+///
+/// This trait is implemented for the primitive integer and floating point types up to 64 bits
+/// and can't be implemented outside of this crate.
+pub trait BinaryElement: Element + Copy + sealed::Sealed {
+    /// Code of the type, stored in the header of a file.
+    ///
+    /// | type | code | type | code |
+    /// |------|-----:|------|-----:|
+    /// | `i8`  | 1 | `u8`  | 5 |
+    /// | `i16` | 2 | `u16` | 6 |
+    /// | `i32` | 3 | `u32` | 7 |
+    /// | `i64` | 4 | `u64` | 8 |
+    /// | `f32` | 9 | `f64` | 10 |
+    const TYPE_CODE: u8;
+
+    /// Size of a value in bytes.
+    const SIZE: usize;
+
+    /// Appends the bytes of this value, in the native byte order, to a buffer.
+    #[doc(hidden)]
+    fn extend_bytes(&self, buffer: &mut Vec<u8>);
+
+    /// Decodes a value from [`SIZE`](BinaryElement::SIZE) bytes in the given byte order.
+    #[doc(hidden)]
+    fn from_bytes(bytes: &[u8], little_endian: bool) -> Self;
+}
+
+macro_rules! binary_element {
+    ($($t:ty => $code:expr),*) => {$(
+        impl sealed::Sealed for $t {}
+        impl BinaryElement for $t {
+            const TYPE_CODE: u8 = $code;
+            const SIZE: usize = std::mem::size_of::<$t>();
+
+            fn extend_bytes(&self, buffer: &mut Vec<u8>) {
+                buffer.extend_from_slice(&self.to_ne_bytes());
+            }
+
+            fn from_bytes(bytes: &[u8], little_endian: bool) -> Self {
+                let bytes = bytes.try_into().expect("a value takes SIZE bytes");
+                if little_endian {
+                    <$t>::from_le_bytes(bytes)
+                } else {
+                    <$t>::from_be_bytes(bytes)
+                }
+            }
+        }
+    )*};
+}
+
+binary_element!(i8 => 1, i16 => 2, i32 => 3, i64 => 4, u8 => 5, u16 => 6, u32 => 7, u64 => 8, f32 => 9, f64 => 10);
+
+/// Name of the type of the given code, used in error messages.
+pub(crate) fn type_name(code: u8) -> &'static str {
+    match code {
+        1 => "i8",
+        2 => "i16",
+        3 => "i32",
+        4 => "i64",
+        5 => "u8",
+        6 => "u16",
+        7 => "u32",
+        8 => "u64",
+        9 => "f32",
+        10 => "f64",
+        _ => "unknown",
+    }
+}
+
+/// Header of a binary matrix file.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Header {
+    pub(crate) little_endian: bool,
+    pub(crate) type_code: u8,
+    pub(crate) has_mask: bool,
+    pub(crate) nrows: usize,
+    pub(crate) ncols: usize,
+    pub(crate) labels_len: usize,
+    pub(crate) data_offset: usize,
+}
+
+impl Header {
+    fn to_bytes(&self) -> [u8; HEADER_SIZE] {
+        let mut bytes = [0u8; HEADER_SIZE];
+        bytes[0..8].copy_from_slice(MAGIC);
+        bytes[8..10].copy_from_slice(&VERSION.to_le_bytes());
+        bytes[10] = if self.little_endian { 0 } else { 1 };
+        bytes[11] = self.type_code;
+        bytes[12] = self.has_mask as u8;
+        bytes[16..24].copy_from_slice(&(self.nrows as u64).to_le_bytes());
+        bytes[24..32].copy_from_slice(&(self.ncols as u64).to_le_bytes());
+        bytes[32..40].copy_from_slice(&(self.labels_len as u64).to_le_bytes());
+        bytes[40..48].copy_from_slice(&(self.data_offset as u64).to_le_bytes());
+        bytes
+    }
+
+    /// Decodes and validates a header.
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < HEADER_SIZE || &bytes[0..8] != MAGIC {
+            return Err(invalid("not a binary data matrix file"));
+        }
+        let version = u16::from_le_bytes([bytes[8], bytes[9]]);
+        if version != VERSION {
+            return Err(Error::UnsupportedVersion {
+                version,
+                supported: VERSION,
+            });
+        }
+        let little_endian = match bytes[10] {
+            0 => true,
+            1 => false,
+            other => return Err(invalid(&format!("unknown byte order: {other}"))),
+        };
+        let field = |at: usize| -> Result<usize, Error> {
+            let value = u64::from_le_bytes(bytes[at..at + 8].try_into().expect("8 bytes"));
+            usize::try_from(value).map_err(|_| invalid("size does not fit in memory"))
+        };
+        let header = Header {
+            little_endian,
+            type_code: bytes[11],
+            has_mask: bytes[12] & 1 != 0,
+            nrows: field(16)?,
+            ncols: field(24)?,
+            labels_len: field(32)?,
+            data_offset: field(40)?,
+        };
+        if HEADER_SIZE.checked_add(header.labels_len).is_none_or(|end| header.data_offset < end) {
+            return Err(invalid("values overlap the label tables"));
+        }
+        if header.n_values().is_none() {
+            return Err(invalid("the matrix is too large"));
+        }
+        Ok(header)
+    }
+
+    /// Checks that values are of type `T` and that their size in bytes doesn't overflow.
+    pub(crate) fn check_type<T: BinaryElement>(&self) -> Result<(), Error> {
+        if self.type_code != T::TYPE_CODE {
+            return Err(Error::ElementTypeMismatch {
+                expected: type_name(T::TYPE_CODE),
+                found: type_name(self.type_code),
+            });
+        }
+        if self.data_len::<T>().is_none() {
+            return Err(invalid("the matrix is too large"));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn n_values(&self) -> Option<usize> {
+        self.nrows.checked_mul(self.ncols)
+    }
+
+    /// Size of the values in bytes, if it doesn't overflow.
+    pub(crate) fn data_len<T: BinaryElement>(&self) -> Option<usize> {
+        self.n_values()?.checked_mul(T::SIZE)
+    }
+
+    /// Size of the mask of missing entries in bytes; zero if there is no mask.
+    pub(crate) fn mask_len(&self) -> usize {
+        if self.has_mask {
+            (self.nrows * self.ncols).div_ceil(8)
+        } else {
+            0
+        }
+    }
+}
+
+/// Error of a malformed binary file
+pub(crate) fn invalid(reason: &str) -> Error {
+    Error::InvalidBinaryFormat {
+        reason: reason.to_string(),
+    }
+}
+
+/// Decodes the label tables: `nrows` row labels followed by `ncols` column labels.
+pub(crate) fn parse_labels(bytes: &[u8], nrows: usize, ncols: usize) -> Result<(Vec<String>, Vec<String>), Error> {
+    let mut rest = bytes;
+    let mut labels = Vec::with_capacity(nrows.saturating_add(ncols).min(bytes.len() / 4));
+    for _ in 0..nrows.saturating_add(ncols) {
+        if rest.len() < 4 {
+            return Err(invalid("label tables are truncated"));
+        }
+        let len = u32::from_le_bytes(rest[0..4].try_into().expect("4 bytes")) as usize;
+        if rest.len() < 4 + len {
+            return Err(invalid("label tables are truncated"));
+        }
+        let label = std::str::from_utf8(&rest[4..4 + len]).map_err(|_| invalid("a label is not valid UTF-8"))?;
+        labels.push(label.to_string());
+        rest = &rest[4 + len..];
+    }
+    if !rest.is_empty() {
+        return Err(invalid("label tables are longer than declared"));
+    }
+    let col_labels = labels.split_off(nrows);
+    Ok((labels, col_labels))
+}
+
+/// Tells whether the k-th entry is marked in a mask of missing entries.
+pub(crate) fn is_masked(mask: &[u8], k: usize) -> bool {
+    mask[k / 8] & (1 << (k % 8)) != 0
+}
+
+impl DataMatrixWriter {
+    /// Writes a matrix into a file in the [binary format](crate::binary).
+    ///
+    /// The binary format ignores all the settings of this writer. As for text output, a file name
+    /// ending with e.g. `.gz` gives a compressed file, which can be read by
+    /// [`DataMatrixBuilder::from_binary_file()`] but can't be memory-mapped.
+    ///
+    /// # Example
+    /// ```rust
+    /// use data_matrix::{DataMatrixBuilder, DataMatrixWriter, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let matrix = DataMatrixBuilder::new()
+    ///     .skip_header(true)
+    ///     .index_columns(3, 4)
+    ///     .symmetric(true)
+    ///     .from_file("./tests/test_files/cities_by_distance.csv.gz")?;
+    /// let fname = std::env::temp_dir().join(format!("cities_doc_{}.dmx", std::process::id()));
+    /// DataMatrixWriter::new().to_binary_file(&matrix, &fname)?;
+    /// let copy = DataMatrixBuilder::new().from_binary_file(&fname)?;
+    /// assert_eq!(copy.as_slice(), matrix.as_slice());
+    /// # std::fs::remove_file(&fname)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_binary_file<T: BinaryElement, P: AsRef<Path>>(&self, matrix: &DataMatrix<T>, path: P) -> Result<(), Error> {
+        write_file(path, |writer| self.to_binary_writer(matrix, writer))
+    }
+
+    /// Writes a matrix in the [binary format](crate::binary) to any writer.
+    pub fn to_binary_writer<T: BinaryElement, W: Write>(&self, matrix: &DataMatrix<T>, mut writer: W) -> Result<(), Error> {
+        let mut labels = Vec::new();
+        for label in matrix.row_labels().iter().chain(matrix.col_labels()) {
+            let len = u32::try_from(label.len()).map_err(|_| Error::LabelTooLong {
+                label: label.clone(),
+                max_length: u32::MAX as usize,
+            })?;
+            labels.extend_from_slice(&len.to_le_bytes());
+            labels.extend_from_slice(label.as_bytes());
+        }
+        let data_offset = ((HEADER_SIZE + labels.len()) as u64).next_multiple_of(DATA_ALIGNMENT) as usize;
+        let header = Header {
+            little_endian: cfg!(target_endian = "little"),
+            type_code: T::TYPE_CODE,
+            has_mask: matrix.count_missing() > 0,
+            nrows: matrix.nrows(),
+            ncols: matrix.ncols(),
+            labels_len: labels.len(),
+            data_offset,
+        };
+        writer.write_all(&header.to_bytes())?;
+        labels.resize(data_offset - HEADER_SIZE, 0);
+        writer.write_all(&labels)?;
+
+        write_values(&mut writer, matrix.as_slice().iter())?;
+        if header.has_mask {
+            let mut mask = vec![0u8; header.mask_len()];
+            for i in 0..matrix.nrows() {
+                for j in 0..matrix.ncols() {
+                    if matrix.is_missing(i, j) {
+                        let k = i * matrix.ncols() + j;
+                        mask[k / 8] |= 1 << (k % 8);
+                    }
+                }
+            }
+            writer.write_all(&mask)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: BinaryElement> DataMatrixBuilder<T> {
+    /// Loads a matrix from a file in the [binary format](crate::binary).
+    ///
+    /// Only the element type of this builder is used; values of the file must be of that type,
+    /// otherwise [`Error::ElementTypeMismatch`] is returned. A compressed file is detected from its content
+    /// and decompressed on the fly. To access a large file without reading it whole, see
+    /// `MappedDataMatrix`, available with the `mmap` feature.
+    pub fn from_binary_file<P: AsRef<Path>>(self, filename: P) -> Result<DataMatrix<T>, Error> {
+        let reader = open_file(filename, None)?;
+        self.from_binary_reader(reader)
+    }
+
+    /// Loads a matrix in the [binary format](crate::binary) from any reader.
+    pub fn from_binary_reader<R: BufRead>(self, mut reader: R) -> Result<DataMatrix<T>, Error> {
+        let mut bytes = [0u8; HEADER_SIZE];
+        read_exact(&mut reader, &mut bytes)?;
+        let header = Header::from_bytes(&bytes)?;
+        header.check_type::<T>()?;
+
+        // --- sizes come from the file, so memory is not reserved for them up front
+        let mut labels = Vec::new();
+        (&mut reader).take(header.labels_len as u64).read_to_end(&mut labels)?;
+        if labels.len() < header.labels_len {
+            return Err(invalid("the file is truncated"));
+        }
+        let (row_labels, col_labels) = parse_labels(&labels, header.nrows, header.ncols)?;
+        let padding = (header.data_offset - HEADER_SIZE - header.labels_len) as u64;
+        io::copy(&mut (&mut reader).take(padding), &mut io::sink())?;
+
+        let n_values = header.nrows * header.ncols;
+        let values = read_values(&mut reader, n_values, header.little_endian).map_err(|e| match e.kind() {
+            io::ErrorKind::UnexpectedEof => invalid("the file is truncated"),
+            _ => Error::IoError(e),
+        })?;
+        let mut mask = vec![0u8; header.mask_len()];
+        read_exact(&mut reader, &mut mask)?;
+
+        let matrix = DataMatrix::from_vec(values, row_labels, col_labels)?;
+        Ok(if header.has_mask {
+            matrix.with_missing((0..n_values).map(|k| is_masked(&mask, k)).collect())
+        } else {
+            matrix
+        })
+    }
+}
+
+/// Writes values in the native byte order.
+pub(crate) fn write_values<'a, T: BinaryElement, W: Write>(writer: &mut W, values: impl Iterator<Item = &'a T>) -> io::Result<()> {
+    // --- values are encoded in batches, which is much faster than writing them one by one
+    let mut buffer = Vec::with_capacity(64 * 1024);
+    for value in values {
+        value.extend_bytes(&mut buffer);
+        if buffer.len() >= 64 * 1024 {
+            writer.write_all(&buffer)?;
+            buffer.clear();
+        }
+    }
+    writer.write_all(&buffer)
+}
+
+/// Reads `n_values` values stored in the given byte order.
+pub(crate) fn read_values<T: BinaryElement, R: Read>(reader: &mut R, n_values: usize, little_endian: bool) -> io::Result<Vec<T>> {
+    // --- the number of values comes from the file, so memory is not reserved for all of them up front
+    let mut values = Vec::with_capacity(n_values.min(1 << 20));
+    let mut buffer = vec![0u8; 64 * 1024 / T::SIZE * T::SIZE];
+    while values.len() < n_values {
+        let n_bytes = (n_values - values.len()).saturating_mul(T::SIZE).min(buffer.len());
+        reader.read_exact(&mut buffer[..n_bytes])?;
+        values.extend(buffer[..n_bytes].chunks_exact(T::SIZE).map(|b| T::from_bytes(b, little_endian)));
+    }
+    Ok(values)
+}
+
+/// Reads exactly `buffer.len()` bytes; a file too short is reported as malformed.
+fn read_exact<R: Read>(reader: &mut R, buffer: &mut [u8]) -> Result<(), Error> {
+    reader.read_exact(buffer).map_err(|e| match e.kind() {
+        io::ErrorKind::UnexpectedEof => invalid("the file is truncated"),
+        _ => Error::IoError(e),
+    })
+}
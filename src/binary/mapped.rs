@@ -0,0 +1,207 @@
+//! Memory-mapped access to matrices stored in the binary format.
+use std::fs::File;
+use std::marker::PhantomData;
+use std::path::Path;
+
+use memmap2::Mmap;
+
+use super::{invalid, is_masked, parse_labels, BinaryElement, Header, HEADER_SIZE};
+use crate::indexer::Indexer;
+use crate::{DataMatrix, Error};
+
+/// A matrix stored in the [binary format](crate::binary), mapped into memory rather than read.
+///
+/// Opening a file reads only its header and labels, so it takes the same short time regardless
+/// of the size of the matrix. Values are paged in by the operating system when they are accessed
+/// and are never copied: [`as_slice()`](MappedDataMatrix::as_slice) and [`row()`](MappedDataMatrix::row)
+/// borrow them right from the mapped file.
+///
+/// The file must have been written on a machine of the same byte order and must not be modified
+/// while it is mapped.
+///
+/// # Example
+/// ```rust
+/// use data_matrix::{DataMatrixBuilder, DataMatrixWriter, Error, MappedDataMatrix};
+/// # fn main() -> Result<(), Error> {
+/// let matrix = DataMatrixBuilder::new()
+///     .skip_header(true)
+///     .index_columns(3, 4)
+///     .symmetric(true)
+///     .from_file("./tests/test_files/cities_by_distance.csv.gz")?;
+/// let fname = std::env::temp_dir().join(format!("cities_mapped_doc_{}.dmx", std::process::id()));
+/// DataMatrixWriter::new().to_binary_file(&matrix, &fname)?;
+///
+/// let mapped = MappedDataMatrix::<f64>::open(&fname)?;
+/// assert_eq!(mapped.get_by_label("Tokyo", "Paris"), matrix.get_by_label("Tokyo", "Paris"));
+/// assert_eq!(mapped.row(2), matrix.row(2));
+/// # drop(mapped);
+/// # std::fs::remove_file(&fname)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct MappedDataMatrix<T = f64> {
+    map: Mmap,
+    header: Header,
+    row_labels: Vec<String>,
+    col_labels: Vec<String>,
+    row_index: Indexer,
+    col_index: Indexer,
+    element: PhantomData<T>,
+}
+
+impl<T: BinaryElement> MappedDataMatrix<T> {
+    /// Maps a binary matrix file into memory.
+    ///
+    /// The header is validated: besides errors of a malformed file, [`Error::UnsupportedVersion`] and
+    /// [`Error::ElementTypeMismatch`] are reported, as well as a file written with the other byte order.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let file = File::open(path)?;
+        // SAFETY: the map is read-only; as for any memory-mapped file, its content must not be modified
+        // by other processes while it is mapped, which is documented for this type
+        let map = unsafe { Mmap::map(&file)? };
+
+        let header = Header::from_bytes(&map)?;
+        header.check_type::<T>()?;
+        if header.little_endian != cfg!(target_endian = "little") {
+            return Err(invalid("values are stored in the byte order of another platform"));
+        }
+        let end = header
+            .data_len::<T>()
+            .and_then(|size| size.checked_add(header.data_offset))
+            .and_then(|end| end.checked_add(header.mask_len()));
+        if end.is_none_or(|end| end > map.len()) {
+            return Err(invalid("the file is truncated"));
+        }
+        if header.data_offset % std::mem::align_of::<T>() != 0 {
+            return Err(invalid("values are not aligned"));
+        }
+
+        let labels = &map[HEADER_SIZE..HEADER_SIZE + header.labels_len];
+        let (row_labels, col_labels) = parse_labels(labels, header.nrows, header.ncols)?;
+        if row_labels.is_empty() {
+            return Err(Error::IncorrectMatrixLabels {
+                expected: header.ncols,
+                actual: 0,
+            });
+        }
+        Ok(Self {
+            row_index: Indexer::from_labels(&row_labels),
+            col_index: Indexer::from_labels(&col_labels),
+            row_labels,
+            col_labels,
+            map,
+            header,
+            element: PhantomData,
+        })
+    }
+
+    /// Returns the number of rows.
+    pub fn nrows(&self) -> usize {
+        self.header.nrows
+    }
+
+    /// Returns the number of columns.
+    pub fn ncols(&self) -> usize {
+        self.header.ncols
+    }
+
+    /// Returns the number of rows and columns.
+    pub fn shape(&self) -> (usize, usize) {
+        (self.header.nrows, self.header.ncols)
+    }
+
+    /// Returns all the values of the matrix, packed row-wise, borrowed from the mapped file.
+    pub fn as_slice(&self) -> &[T] {
+        let start = self.header.data_offset;
+        let bytes = &self.map[start..start + self.data_len()];
+        // SAFETY: the range has been checked to lie within the map, its start is aligned for T (the map
+        // itself is page-aligned) and every bit pattern is a valid value of the primitive types
+        // implementing the sealed BinaryElement trait
+        unsafe { std::slice::from_raw_parts(bytes.as_ptr().cast::<T>(), self.nrows() * self.ncols()) }
+    }
+
+    /// Returns the values of the i-th row.
+    ///
+    /// # Panics
+    /// Panics if `i` is out of bounds.
+    pub fn row(&self, i: usize) -> &[T] {
+        assert!(i < self.nrows(), "row index {i} out of bounds for a matrix of {} rows", self.nrows());
+        &self.as_slice()[i * self.ncols()..(i + 1) * self.ncols()]
+    }
+
+    /// Gets the matrix entry at (i, j).
+    pub fn get(&self, i: usize, j: usize) -> Option<T> {
+        if i < self.nrows() && j < self.ncols() {
+            Some(self.as_slice()[i * self.ncols() + j])
+        } else {
+            None
+        }
+    }
+
+    /// Gets the matrix entry by row and column label.
+    pub fn get_by_label(&self, row_label: &str, col_label: &str) -> Option<T> {
+        self.get(self.row_index.get(row_label)?, self.col_index.get(col_label)?)
+    }
+
+    /// Returns the index of a row by its label.
+    pub fn row_index(&self, label: &str) -> Option<usize> {
+        self.row_index.get(label)
+    }
+
+    /// Returns the index of a column by its label.
+    pub fn col_index(&self, label: &str) -> Option<usize> {
+        self.col_index.get(label)
+    }
+
+    /// Returns the label of a row by its index.
+    pub fn row_label(&self, index: usize) -> &String {
+        &self.row_labels[index]
+    }
+
+    /// Returns the label of a column by its index.
+    pub fn col_label(&self, index: usize) -> &String {
+        &self.col_labels[index]
+    }
+
+    /// Returns the row labels.
+    pub fn row_labels(&self) -> &[String] {
+        &self.row_labels
+    }
+
+    /// Returns the column labels.
+    pub fn col_labels(&self) -> &[String] {
+        &self.col_labels
+    }
+
+    /// Checks whether the entry at (i, j) is missing; returns `false` when (i, j) is out of bounds.
+    pub fn is_missing(&self, i: usize, j: usize) -> bool {
+        i < self.nrows() && j < self.ncols() && self.mask().is_some_and(|mask| is_masked(mask, i * self.ncols() + j))
+    }
+
+    /// Counts the missing entries of this matrix.
+    pub fn count_missing(&self) -> usize {
+        let n_values = self.nrows() * self.ncols();
+        self.mask().map_or(0, |mask| (0..n_values).filter(|&k| is_masked(mask, k)).count())
+    }
+
+    /// Copies the whole matrix into memory.
+    pub fn to_dense(&self) -> DataMatrix<T> {
+        let matrix = DataMatrix::from_vec(self.as_slice().to_vec(), self.row_labels.clone(), self.col_labels.clone())
+            .expect("the shape of a mapped matrix is given by its labels");
+        match self.mask() {
+            Some(mask) => matrix.with_missing((0..self.nrows() * self.ncols()).map(|k| is_masked(mask, k)).collect()),
+            None => matrix,
+        }
+    }
+
+    /// Size of the values in bytes.
+    fn data_len(&self) -> usize {
+        self.header.data_len::<T>().expect("the size of the values is checked when the file is opened")
+    }
+
+    /// The mask of missing entries, if the file holds one.
+    fn mask(&self) -> Option<&[u8]> {
+        let start = self.header.data_offset + self.data_len();
+        self.header.has_mask.then(|| &self.map[start..start + self.header.mask_len()])
+    }
+}
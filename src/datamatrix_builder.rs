@@ -1,11 +1,37 @@
-use flate2::read;
-use std::collections::HashMap;
-use std::ffi::OsStr;
-use std::fs::File;
-use std::io::{self, BufRead, BufReader, ErrorKind};
-use std::path::Path;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
 
-use crate::{DataMatrix, Error};
+use crate::accumulator::Accumulator;
+use crate::indexer::Indexer;
+use crate::io_utils::{decompress, guess_separator, open_file, Compression};
+use crate::matrix_market::{read_label_file, read_matrix_market, MatrixMarketEntries};
+use crate::phylip::read_phylip;
+use crate::{DataMatrix, Element, Error, PackedDataMatrix, SparseDataMatrix};
+
+#[cfg(feature = "rayon")]
+mod parallel;
+
+/// Defines how [`DataMatrixBuilder`] resolves an entry given more than once in the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum DuplicatePolicy {
+    /// Return [`Error::ConflictingValues`] when an entry is given again with a different value
+    Error,
+    /// Keep the value given first
+    KeepFirst,
+    /// Keep the value given last; this is the default
+    KeepLast,
+    /// Store the average of all the values given for an entry; [`Error::ValueOverflow`] is returned
+    /// when the sum of integer values overflows
+    Average,
+    /// Store the smallest of the values given for an entry
+    Min,
+    /// Store the largest of the values given for an entry
+    Max,
+    /// Store the sum of all the values given for an entry; [`Error::ValueOverflow`] is returned
+    /// when the sum of integer values overflows
+    Sum,
+}
 
 /// A builder for loading labeled matrices from plain text, CSV, or TSV files.
 ///
@@ -78,12 +104,19 @@ use crate::{DataMatrix, Error};
 /// # Notes
 /// - Columns are indexed starting **from 0**
 /// - field separator must be a single character (with an exception for `' '`, see below); if not given, the value will be inferred from the file extension,
-///     e.g. `'\t'` for `.tsv`
+///   e.g. `'\t'` for `.tsv`
 /// - when `' '` (a space) is used a separator, the builder splits by all white spaces, i.e.  `str.split_whitespace(&self)`
 ///   method is used
 /// - `.symmetric(true)` ensures that if (i,j) is set, (j,i) will also be set automatically.
+/// - with the `serde` feature, the builder can be serialized, e.g. to keep its settings in a configuration file;
+///   settings absent from the serialized form take their default values
 #[derive(Debug, Clone)]
-pub struct DataMatrixBuilder {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(default = "default_builder", bound(deserialize = "T: Element + serde::Deserialize<'de>"))
+)]
+pub struct DataMatrixBuilder<T = f64> {
     row_label_col: usize,
     col_label_col: usize,
     data_col: usize,
@@ -91,8 +124,18 @@ pub struct DataMatrixBuilder {
     col_idx_col: Option<usize>,
     separator: Option<char>,
     symmetric: bool,
+    store_diagonal: bool,
     skip_header: bool,
-    labels: Option<Vec<String>>,
+    corner_label: bool,
+    pub(crate) labels: Option<Vec<String>>,
+    strict_phylip_names: bool,
+    label_files: Option<(PathBuf, PathBuf)>,
+    missing_value: T,
+    na_tokens: Vec<String>,
+    duplicates: DuplicatePolicy,
+    compression: Option<Compression>,
+    #[cfg(feature = "rayon")]
+    parallel: bool,
 }
 
 #[allow(clippy::new_without_default)]
@@ -100,6 +143,9 @@ impl DataMatrixBuilder {
     /// Creates just a new builder.
     ///
     /// Now use its methods to set up column indexes (e.g. [`label_columns()`](DataMatrixBuilder::label_columns)), then provide some data (e.g. [`from_file()`](DataMatrixBuilder::from_file))
+    ///
+    /// The builder creates matrices of `f64` values; call [`element_type()`](DataMatrixBuilder::element_type)
+    /// to load values of another type.
     pub fn new() -> Self {
         Self {
             row_label_col: 0,
@@ -109,8 +155,72 @@ impl DataMatrixBuilder {
             col_idx_col: None,
             separator: None,
             symmetric: false,
+            store_diagonal: true,
             skip_header: false,
+            corner_label: false,
             labels: None,
+            strict_phylip_names: false,
+            label_files: None,
+            missing_value: 0.0,
+            na_tokens: vec!["NA".to_string()],
+            duplicates: DuplicatePolicy::KeepLast,
+            compression: None,
+            #[cfg(feature = "rayon")]
+            parallel: false,
+        }
+    }
+}
+
+/// Settings of a builder which are missing from its serialized form.
+#[cfg(feature = "serde")]
+fn default_builder<T: Element>() -> DataMatrixBuilder<T> {
+    DataMatrixBuilder::new().element_type()
+}
+
+impl<T: Element> DataMatrixBuilder<T> {
+    /// Sets the type of values of the matrix, `f64` by default.
+    ///
+    /// Values are parsed with [`FromStr`](std::str::FromStr); the value of missing entries is reset
+    /// to the default of the new type (e.g. `0` or `false`), all other settings are kept.
+    ///
+    /// The type must implement [`Element`] rather than just `FromStr`: the builder also needs a default value
+    /// for missing entries, comparison and formatting to detect and report conflicting duplicates, and `Send + Sync`
+    /// to parse in parallel. All the methods of [`Element`] are provided, so any other type which meets these bounds
+    /// only needs an empty `impl Element for MyType {}`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use data_matrix::{DataMatrix, DataMatrixBuilder, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let matrix: DataMatrix<f32> = DataMatrixBuilder::new()
+    ///     .symmetric(true)
+    ///     .element_type::<f32>()
+    ///     .from_file("./tests/test_files/three_columns_short.txt")?;
+    /// assert_eq!(matrix.get_by_label("Bob", "Alice"), Some(1.2f32));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn element_type<U: Element>(self) -> DataMatrixBuilder<U> {
+        DataMatrixBuilder {
+            row_label_col: self.row_label_col,
+            col_label_col: self.col_label_col,
+            data_col: self.data_col,
+            row_idx_col: self.row_idx_col,
+            col_idx_col: self.col_idx_col,
+            separator: self.separator,
+            symmetric: self.symmetric,
+            store_diagonal: self.store_diagonal,
+            skip_header: self.skip_header,
+            corner_label: self.corner_label,
+            labels: self.labels,
+            strict_phylip_names: self.strict_phylip_names,
+            label_files: self.label_files,
+            missing_value: U::default(),
+            na_tokens: self.na_tokens,
+            duplicates: self.duplicates,
+            compression: self.compression,
+            #[cfg(feature = "rayon")]
+            parallel: self.parallel,
         }
     }
 
@@ -180,12 +290,32 @@ impl DataMatrixBuilder {
         self
     }
 
+    /// Sets the compression of the input.
+    ///
+    /// By default the compression is detected from the first bytes of the input, so a compressed file
+    /// is recognised even if its extension is wrong. Formats other than gzip require cargo features,
+    /// see [`Compression`].
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
     /// If set to `true`, the first line of the file should be skipped as a header.
     pub fn skip_header(mut self, if_header: bool) -> Self {
         self.skip_header = if_header;
         self
     }
 
+    /// If set to `true`, the header of a wide table starts with a label of the row label column, e.g. `gene`
+    /// in `gene,S1,S2`.
+    ///
+    /// An empty leading field of the header is always taken for such a label;
+    /// see [`from_wide_file()`](DataMatrixBuilder::from_wide_file).
+    pub fn corner_label(mut self, if_corner: bool) -> Self {
+        self.corner_label = if_corner;
+        self
+    }
+
     /// Sets whether the matrix should be treated as symmetric.
     ///
     /// If enabled, for every entry `(row, col, value)`, the symmetric entry `(col, row, value)`
@@ -195,6 +325,124 @@ impl DataMatrixBuilder {
         self
     }
 
+    /// Sets whether a packed matrix keeps its diagonal, `true` by default.
+    ///
+    /// Used only by [`from_file_packed()`](DataMatrixBuilder::from_file_packed) and
+    /// [`from_reader_packed()`](DataMatrixBuilder::from_reader_packed). Without the diagonal, diagonal entries
+    /// of the input are ignored and read as `T::default()`, which suits e.g. distance matrices.
+    pub fn store_diagonal(mut self, if_stored: bool) -> Self {
+        self.store_diagonal = if_stored;
+        self
+    }
+
+    /// Sets the value stored for missing entries.
+    ///
+    /// An entry is missing when the input does not provide a value for it, or provides one of the
+    /// [NA tokens](DataMatrixBuilder::na_tokens) instead. The default value is `0.0`; use `f64::NAN`
+    /// to make missing entries stand out. Missing entries can be found with [`DataMatrix::is_missing()`].
+    pub fn missing_value(mut self, value: T) -> Self {
+        self.missing_value = value;
+        self
+    }
+
+    /// Sets the tokens that denote a missing value in the input.
+    ///
+    /// Tokens are compared with a field after trimming white spaces. By default only `"NA"` is recognised;
+    /// other popular choices are `"nan"`, `"-"` or `""` (an empty field).
+    ///
+    /// # Example
+    /// ```rust
+    /// use data_matrix::DataMatrixBuilder;
+    /// let builder = DataMatrixBuilder::new().na_tokens(["NA", "nan", "-", ""]);
+    /// ```
+    pub fn na_tokens<I, S>(mut self, tokens: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.na_tokens = tokens.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets how to resolve an entry given more than once in the input.
+    ///
+    /// With [`symmetric(true)`](DataMatrixBuilder::symmetric), `(row, col)` and `(col, row)` denote the same entry.
+    /// By default the last value given for an entry is kept; NA tokens are never counted as duplicates.
+    /// Policies that aggregate values (sum, average, minimum and maximum) are defined for numeric
+    /// [element types](Element) only; for other types they fail with [`Error::UnsupportedPolicy`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use data_matrix::{DataMatrixBuilder, DuplicatePolicy, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let result = DataMatrixBuilder::new()
+    ///     .symmetric(true)
+    ///     .duplicates(DuplicatePolicy::Error)
+    ///     .from_file("./tests/test_files/three_columns_duplicated.txt");
+    /// assert!(matches!(result, Err(Error::ConflictingValues { first_line: 2, second_line: 4, .. })));
+    ///
+    /// let matrix = DataMatrixBuilder::new()
+    ///     .symmetric(true)
+    ///     .duplicates(DuplicatePolicy::Average)
+    ///     .from_file("./tests/test_files/three_columns_duplicated.txt")?;
+    /// assert_eq!(matrix.get_by_label("Bob", "Alice"), Some(2.3));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn duplicates(mut self, policy: DuplicatePolicy) -> Self {
+        self.duplicates = policy;
+        self
+    }
+
+    /// If set to `true`, the three- and five-column formats are parsed by all the threads of the `rayon` pool.
+    ///
+    /// The input is read in large blocks, each split on line boundaries into chunks parsed concurrently;
+    /// their results are merged in the order of the input, so the matrix is identical to the one
+    /// loaded sequentially (and so are errors). Decompression, if any, remains sequential.
+    /// Only the input being parsed at the moment is kept in memory, besides the matrix itself.
+    /// Requires the `rayon` feature.
+    ///
+    /// # Example
+    /// ```rust
+    /// use data_matrix::{DataMatrixBuilder, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let matrix = DataMatrixBuilder::new()
+    ///     .skip_header(true)
+    ///     .index_columns(3, 4)
+    ///     .symmetric(true)
+    ///     .parallel(true)
+    ///     .from_file("./tests/test_files/cities_by_distance.csv.gz")?;
+    /// assert_eq!(matrix.nrows(), 15);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn parallel(mut self, if_parallel: bool) -> Self {
+        self.parallel = if_parallel;
+        self
+    }
+
+    /// Sets whether taxon names in a PHYLIP file follow the strict format.
+    ///
+    /// In the strict format a name occupies exactly the first 10 characters of a line (and may contain spaces);
+    /// by default names are relaxed, i.e. terminated by the first white space.
+    /// This setting is used only by [`from_phylip()`](DataMatrixBuilder::from_phylip).
+    pub fn strict_phylip_names(mut self, if_strict: bool) -> Self {
+        self.strict_phylip_names = if_strict;
+        self
+    }
+
+    /// Sets companion files with row and column labels of a Matrix Market file, one label per line.
+    ///
+    /// Labels stored in the Matrix Market file itself take precedence; for a symmetric matrix both paths
+    /// may point to the same file. Label files may be compressed.
+    /// This setting is used only by [`from_matrix_market()`](DataMatrixBuilder::from_matrix_market)
+    /// and the related methods.
+    pub fn label_files<P: AsRef<Path>, Q: AsRef<Path>>(mut self, row_labels: P, col_labels: Q) -> Self {
+        self.label_files = Some((row_labels.as_ref().to_path_buf(), col_labels.as_ref().to_path_buf()));
+        self
+    }
+
     /// Creates a new [`DataMatrix`] from a given 1D vector of data.
     ///
     /// This method is devised to turn a 1D column of numbers into a **square** (usually symmetrix)
@@ -233,7 +481,7 @@ impl DataMatrixBuilder {
     /// # }
     /// ```
     ///
-    pub fn from_data(self, data: &[f64]) -> Result<DataMatrix, Error> {
+    pub fn from_data(self, data: &[T]) -> Result<DataMatrix<T>, Error> {
         let len = data.len();
         let n = (len as f64).sqrt() as usize;
         if n * n != len {
@@ -241,6 +489,12 @@ impl DataMatrixBuilder {
         }
 
         let (row_labels, col_labels) = match &self.labels {
+            Some(given) if given.len() != n => {
+                return Err(Error::IncorrectMatrixLabels {
+                    expected: given.len(),
+                    actual: n,
+                })
+            }
             Some(given) => (given.clone(), given.clone()),
             None => {
                 let rows = (0..n).map(|i| format!("row-{}", i + 1)).collect();
@@ -249,113 +503,437 @@ impl DataMatrixBuilder {
             }
         };
 
-        let mut matrix = Vec::with_capacity(n);
-        for i in 0..n {
-            let start = i * n;
-            let end = start + n;
-            matrix.push(data[start..end].to_vec());
-        }
-
-        DataMatrix::new(matrix, row_labels, col_labels)
+        DataMatrix::from_vec(data.to_vec(), row_labels, col_labels)
     }
 
     /// Loads the matrix from the given file path according to the current builder settings.
-    pub fn from_file<P: AsRef<Path>>(self, filename: P) -> Result<DataMatrix, Error> {
-        if let Some(ref labels) = self.labels {
-            return self.read_one_column(filename, self.data_col, labels.clone());
-        }
+    ///
+    /// Unless set explicitly with [`separator()`](DataMatrixBuilder::separator) and
+    /// [`compression()`](DataMatrixBuilder::compression), the separator is inferred from the file extension
+    /// and the compression is detected from the content of the file.
+    pub fn from_file<P: AsRef<Path>>(self, filename: P) -> Result<DataMatrix<T>, Error> {
+        let separator = match self.separator {
+            None => guess_separator(&filename),
+            Some(c) => c,
+        };
+        let reader = open_file(filename, self.compression)?;
+        self.read_long(reader, separator)
+    }
 
-        let mut row_indexer = Indexer::new();
-        let mut col_indexer = Indexer::new();
+    /// Loads the matrix from any buffered reader according to the current builder settings.
+    ///
+    /// This method accepts everything [`from_file()`](DataMatrixBuilder::from_file) does, e.g. stdin,
+    /// an in-memory buffer or an entry of an archive. Since there is no file name to infer the separator from,
+    /// it defaults to `' '` unless set with [`separator()`](DataMatrixBuilder::separator); compression
+    /// is detected as for a file.
+    ///
+    /// # Example
+    /// ```rust
+    /// use data_matrix::{Compression, DataMatrixBuilder, Error};
+    /// use std::fs::File;
+    /// use std::io::BufReader;
+    /// # fn main() -> Result<(), Error> {
+    /// let file = File::open("./tests/test_files/cities_by_distance.csv.gz")?;
+    /// let matrix = DataMatrixBuilder::new()
+    ///     .separator(',')
+    ///     .compression(Compression::Gzip)
+    ///     .skip_header(true)
+    ///     .index_columns(3, 4)
+    ///     .symmetric(true)
+    ///     .from_reader(BufReader::new(file))?;
+    /// assert_eq!(matrix.nrows(), 15);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_reader<R: BufRead>(self, reader: R) -> Result<DataMatrix<T>, Error> {
+        let reader = decompress(reader, self.compression)?;
+        self.read_long(reader, self.separator.unwrap_or(' '))
+    }
 
+    /// Loads the matrix from text according to the current builder settings.
+    ///
+    /// The separator defaults to `' '`; the compression setting is ignored.
+    ///
+    /// # Example
+    /// ```rust
+    /// use data_matrix::{DataMatrixBuilder, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let matrix = DataMatrixBuilder::new()
+    ///     .symmetric(true)
+    ///     .from_str("Alice Bob 1.2\nBob John 2.4\n")?;
+    /// assert_eq!(matrix.get_by_label("Bob", "Alice"), Some(1.2));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_str(self, text: &str) -> Result<DataMatrix<T>, Error> {
+        self.read_long(text.as_bytes(), self.separator.unwrap_or(' '))
+    }
+
+    /// Loads a symmetric matrix from the given file path into packed storage, which keeps only its upper triangle.
+    ///
+    /// The file is read as by [`from_file()`](DataMatrixBuilder::from_file) with [`symmetric()`](DataMatrixBuilder::symmetric)
+    /// set to `true`: (i, j) and (j, i) are the same entry. Whether the diagonal is stored is set
+    /// with [`store_diagonal()`](DataMatrixBuilder::store_diagonal).
+    ///
+    /// # Example
+    /// ```rust
+    /// use data_matrix::{DataMatrixBuilder, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let packed = DataMatrixBuilder::new()
+    ///     .skip_header(true)
+    ///     .index_columns(3, 4)
+    ///     .from_file_packed("./tests/test_files/cities_by_distance.csv.gz")?;
+    /// assert_eq!(packed.as_slice().len(), 15 * 16 / 2);
+    /// assert_eq!(packed.get_by_label("Tokyo", "Paris"), packed.get_by_label("Paris", "Tokyo"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_file_packed<P: AsRef<Path>>(self, filename: P) -> Result<PackedDataMatrix<T>, Error> {
         let separator = match self.separator {
             None => guess_separator(&filename),
             Some(c) => c,
         };
+        let reader = open_file(filename, self.compression)?;
+        self.symmetric(true).read_packed(reader, separator)
+    }
 
-        let lines = parse_plain(filename, separator, self.skip_header)?;
-        // ---------- Build the label_to_index map if we have explicit entry indexing
-        if let (Some(r_idx), Some(c_idx)) = (self.row_idx_col, self.col_idx_col) {
-            for (line_no, parts) in lines.iter().enumerate() {
-                let row_idx: usize = parts[r_idx].parse().map_err(|_| Error::ParseError {
-                    line: line_no,
-                    content: parts[r_idx].to_string(),
-                })?;
-                let col_idx: usize = parts[c_idx].parse().map_err(|_| Error::ParseError {
-                    line: line_no,
-                    content: parts[c_idx].to_string(),
-                })?;
-                row_indexer.add_explicit(&parts[self.row_label_col], row_idx);
-                if self.symmetric {
-                    row_indexer.add_explicit(&parts[self.col_label_col], col_idx);
-                } else {
-                    col_indexer.add_explicit(&parts[self.col_label_col], col_idx);
-                }
-            }
-        } else {
-            // ---------- Build the label_to_index map if we don't have explicit entry indexing
-            for parts in &lines {
-                row_indexer.add(&parts[self.row_label_col]);
-                if self.symmetric {
-                    row_indexer.add(&parts[self.col_label_col]);
-                } else {
-                    col_indexer.add(&parts[self.col_label_col]);
+    /// Loads a symmetric matrix from any buffered reader into packed storage.
+    ///
+    /// The input is read as by [`from_reader()`](DataMatrixBuilder::from_reader); see
+    /// [`from_file_packed()`](DataMatrixBuilder::from_file_packed) for details.
+    pub fn from_reader_packed<R: BufRead>(self, reader: R) -> Result<PackedDataMatrix<T>, Error> {
+        let reader = decompress(reader, self.compression)?;
+        let separator = self.separator.unwrap_or(' ');
+        self.symmetric(true).read_packed(reader, separator)
+    }
+
+    /// Loads the matrix from the given file path into sparse storage, which keeps only the entries given in the file.
+    ///
+    /// The file is read as by [`from_file()`](DataMatrixBuilder::from_file), but no memory is taken by
+    /// entries missing from the input; this suits e.g. contact maps or interaction networks, which list
+    /// a small fraction of all pairs of labels.
+    ///
+    /// # Example
+    /// ```rust
+    /// use data_matrix::{DataMatrixBuilder, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let matrix = DataMatrixBuilder::new()
+    ///     .from_file_sparse("./tests/test_files/three_columns_short.txt")?;
+    /// assert_eq!(matrix.nnz(), 2);
+    /// assert_eq!(matrix.get_by_label("Alice", "Bob"), Some(1.2));
+    /// assert!(matrix.is_missing(0, 1));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_file_sparse<P: AsRef<Path>>(self, filename: P) -> Result<SparseDataMatrix<T>, Error> {
+        let separator = match self.separator {
+            None => guess_separator(&filename),
+            Some(c) => c,
+        };
+        let reader = open_file(filename, self.compression)?;
+        self.read_sparse(reader, separator)
+    }
+
+    /// Loads the matrix from any buffered reader into sparse storage.
+    ///
+    /// The input is read as by [`from_reader()`](DataMatrixBuilder::from_reader); see
+    /// [`from_file_sparse()`](DataMatrixBuilder::from_file_sparse) for details.
+    pub fn from_reader_sparse<R: BufRead>(self, reader: R) -> Result<SparseDataMatrix<T>, Error> {
+        let reader = decompress(reader, self.compression)?;
+        self.read_sparse(reader, self.separator.unwrap_or(' '))
+    }
+
+    /// Reads a matrix in the three-column, five-column or single-column format.
+    ///
+    /// The input is processed line by line: only the matrix being built is kept in memory,
+    /// so its size need not be known up front.
+    fn read_long<R: BufRead>(&self, reader: R, separator: char) -> Result<DataMatrix<T>, Error> {
+        if let Some(ref labels) = self.labels {
+            return self.read_one_column(reader, self.data_col, labels.clone());
+        }
+        self.collect_long(reader, separator, false)?.finish()
+    }
+
+    /// Reads a symmetric matrix in the three-column, five-column or single-column format into packed storage.
+    fn read_packed<R: BufRead>(&self, reader: R, separator: char) -> Result<PackedDataMatrix<T>, Error> {
+        if let Some(ref labels) = self.labels {
+            let matrix = self.read_one_column(reader, self.data_col, labels.clone())?;
+            return PackedDataMatrix::from_dense(&matrix, self.store_diagonal);
+        }
+        self.collect_long(reader, separator, false)?.finish_packed(self.store_diagonal)
+    }
+
+    /// Reads a matrix in the three-column, five-column or single-column format into sparse storage.
+    fn read_sparse<R: BufRead>(&self, reader: R, separator: char) -> Result<SparseDataMatrix<T>, Error> {
+        if let Some(ref labels) = self.labels {
+            let matrix = self.read_one_column(reader, self.data_col, labels.clone())?;
+            // --- every value given in the input is stored, even if it equals the fill value, as in the long layouts
+            let mut triplets = Vec::new();
+            for (i, row) in matrix.rows().enumerate() {
+                for (j, value) in row.iter().enumerate() {
+                    if !matrix.is_missing(i, j) {
+                        triplets.push((i, j, value.clone()));
+                    }
                 }
             }
+            return Ok(SparseDataMatrix::from_triplets(triplets, labels.clone(), labels.clone(), self.missing_value.clone()));
         }
+        self.collect_long(reader, separator, true)?.finish_sparse(self.missing_value.clone())
+    }
 
-        if self.symmetric {
-            col_indexer = row_indexer.clone();
+    /// Collects entries of the three- or five-column format, into `sparse` storage if requested.
+    fn collect_long<R: BufRead>(&self, reader: R, separator: char, sparse: bool) -> Result<LongMatrix<T>, Error> {
+        #[cfg(feature = "rayon")]
+        if self.parallel {
+            return self.collect_long_parallel(reader, separator, sparse);
         }
-        let mut data = vec![vec![0.0; col_indexer.max_index()]; row_indexer.max_index()];
-        let row_labels = row_indexer.to_vec();
-        let col_labels = col_indexer.to_vec();
-
-        for (line_no, parts) in lines.into_iter().enumerate() {
-            let i_row = row_indexer.index(&parts[self.row_label_col]);
-            let j_col = col_indexer.index(&parts[self.col_label_col]);
-            let value: f64 = parts[self.data_col]
-                .parse()
-                .map_err(|_| Error::ParseError {
-                    line: line_no,
-                    content: parts[self.data_col].to_string()
-                })?;
-            data[i_row][j_col] = value;
-            if self.symmetric {
-                data[j_col][i_row] = value;
-            }
+
+        let mut matrix = LongMatrix::new(self, sparse);
+        for_each_line(reader, self.skip_header, |line_no, line| {
+            let (entry, row_label, col_label) = self.parse_entry(line_no, line, separator, &mut matrix.ids)?;
+            matrix.add(entry, row_label, col_label)
+        })?;
+        Ok(matrix)
+    }
+
+    /// Parses a line of the three- or five-column format; row and column labels are given ids by `ids`.
+    ///
+    /// Returns the parsed entry, followed by its row and column labels.
+    fn parse_entry<'l>(
+        &self,
+        line_no: usize,
+        line: &'l str,
+        separator: char,
+        ids: &mut LabelIds,
+    ) -> Result<(Entry<T>, &'l str, &'l str), Error> {
+        let parts = split_fields(line, separator);
+        let needed = self.columns_needed();
+        if parts.len() < needed {
+            return Err(Error::NotEnoughColumns {
+                line: line_no,
+                needed,
+                content: line.to_string(),
+            });
         }
+        let indices = match (self.row_idx_col, self.col_idx_col) {
+            (Some(r_idx), Some(c_idx)) => Some((
+                parse_index(parts[r_idx], line_no)?,
+                parse_index(parts[c_idx], line_no)?,
+            )),
+            _ => None,
+        };
+        let (row_label, col_label) = (parts[self.row_label_col], parts[self.col_label_col]);
+        // --- labels are registered even if the value is missing
+        let (row, col) = ids.add(row_label, col_label);
+        let value = self.parse_value(parts[self.data_col], line_no)?;
+        let entry = Entry {
+            line: line_no,
+            row,
+            col,
+            indices,
+            value,
+        };
 
-        DataMatrix::new(data, row_labels, col_labels)
+        Ok((entry, row_label, col_label))
+    }
+
+    /// Loads a matrix stored as a wide (dense) table from the given file.
+    ///
+    /// The first non-comment line of a wide file holds column labels; each following line holds
+    /// a row label followed by exactly one value for each column:
+    /// ```text
+    /// gene,S1,S2,S3
+    /// G1,0.81,0.93,0.15
+    /// G2,0.72,1.00,0.33
+    /// ```
+    /// The header may start with a label for the row label column (`gene` above), which must be declared
+    /// with [`corner_label()`](DataMatrixBuilder::corner_label) unless it's empty, or omit it; every row must
+    /// then have one field more than the column labels. Whitespace around labels and values is ignored. The separator is inferred from the file extension
+    /// unless set with [`separator()`](DataMatrixBuilder::separator); gzipped files are supported.
+    /// Column settings such as [`label_columns()`](DataMatrixBuilder::label_columns) or
+    /// [`skip_header()`](DataMatrixBuilder::skip_header) do not apply to this format.
+    ///
+    /// # Example
+    /// ```rust
+    /// use data_matrix::{DataMatrixBuilder, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let matrix = DataMatrixBuilder::new().corner_label(true).from_wide_file("./tests/test_files/wide_short.csv")?;
+    /// assert_eq!(matrix.nrows(), 2);
+    /// assert_eq!(matrix.ncols(), 3);
+    /// assert_eq!(matrix.get_by_label("G2", "S3"), Some(0.33));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_wide_file<P: AsRef<Path>>(self, filename: P) -> Result<DataMatrix<T>, Error> {
+        let separator = match self.separator {
+            None => guess_separator(&filename),
+            Some(c) => c,
+        };
+        let reader = open_file(filename, self.compression)?;
+        self.read_wide(reader, separator)
+    }
+
+    /// Loads a matrix stored as a wide (dense) table from any buffered reader.
+    ///
+    /// See [`from_wide_file()`](DataMatrixBuilder::from_wide_file) for the description of the format and
+    /// [`from_reader()`](DataMatrixBuilder::from_reader) for the default settings.
+    pub fn from_wide_reader<R: BufRead>(self, reader: R) -> Result<DataMatrix<T>, Error> {
+        let reader = decompress(reader, self.compression)?;
+        self.read_wide(reader, self.separator.unwrap_or(' '))
+    }
+
+    /// Loads a distance matrix from a PHYLIP file.
+    ///
+    /// The first line of the file gives the number of taxa; each following row starts with a taxon name,
+    /// followed by the distances. Both the square and the lower-triangular layouts (with or without
+    /// the diagonal) are recognised and rows may be wrapped over several lines. Names are relaxed by default,
+    /// see [`strict_phylip_names()`](DataMatrixBuilder::strict_phylip_names). A square matrix must be symmetric,
+    /// otherwise [`Error::NotSymmetric`] is returned.
+    ///
+    /// # Example
+    /// ```rust
+    /// use data_matrix::{DataMatrixBuilder, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let matrix = DataMatrixBuilder::new().from_phylip("./tests/test_files/lower_triangle.phy")?;
+    /// assert_eq!(matrix.nrows(), 4);
+    /// assert_eq!(matrix.get_by_label("Gorilla", "Human"), Some(0.0823));
+    /// assert_eq!(matrix.get_by_label("Human", "Gorilla"), Some(0.0823));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_phylip<P: AsRef<Path>>(self, filename: P) -> Result<DataMatrix<T>, Error> {
+        let reader = open_file(filename, self.compression)?;
+        read_phylip(reader, self.strict_phylip_names)
+    }
+
+    /// Loads a distance matrix in the PHYLIP format from any buffered reader.
+    ///
+    /// See [`from_phylip()`](DataMatrixBuilder::from_phylip) for details.
+    pub fn from_phylip_reader<R: BufRead>(self, reader: R) -> Result<DataMatrix<T>, Error> {
+        let reader = decompress(reader, self.compression)?;
+        read_phylip(reader, self.strict_phylip_names)
+    }
+
+    /// Loads a matrix from a Matrix Market file in the coordinate format.
+    ///
+    /// The `general` and `symmetric` qualifiers are recognised for `real` and `integer` matrices; entries
+    /// of a symmetric file are mirrored, as are the entries of a general one when
+    /// [`symmetric(true)`](DataMatrixBuilder::symmetric) is set. Indices are 1-based; entries which are
+    /// not listed are [missing](DataMatrix::is_missing) and hold the [missing value](DataMatrixBuilder::missing_value),
+    /// while entries listed more than once are resolved by the [duplicate policy](DataMatrixBuilder::duplicates).
+    ///
+    /// Row and column labels are taken from `% row_labels:` and `% col_labels:` comments of the file,
+    /// as written by [`Layout::MatrixMarket`](crate::Layout::MatrixMarket). Otherwise they are read from
+    /// [label files](DataMatrixBuilder::label_files), taken from [`labels()`](DataMatrixBuilder::labels)
+    /// or generated as for [`from_data()`](DataMatrixBuilder::from_data), in this order.
+    ///
+    /// # Example
+    /// ```rust
+    /// use data_matrix::{DataMatrixBuilder, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let matrix = DataMatrixBuilder::new().from_matrix_market("./tests/test_files/symmetric.mtx")?;
+    /// assert_eq!(matrix.shape(), (3, 3));
+    /// assert_eq!(matrix.get_by_label("Alice", "John"), Some(2.0));
+    /// assert_eq!(matrix.get_by_label("John", "Alice"), Some(2.0));
+    /// assert!(matrix.is_missing(0, 0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_matrix_market<P: AsRef<Path>>(self, filename: P) -> Result<DataMatrix<T>, Error> {
+        let reader = open_file(filename, self.compression)?;
+        self.read_matrix_market(reader)
+    }
+
+    /// Loads a matrix in the Matrix Market coordinate format from any buffered reader.
+    ///
+    /// See [`from_matrix_market()`](DataMatrixBuilder::from_matrix_market) for details.
+    pub fn from_matrix_market_reader<R: BufRead>(self, reader: R) -> Result<DataMatrix<T>, Error> {
+        let reader = decompress(reader, self.compression)?;
+        self.read_matrix_market(reader)
+    }
+
+    /// Loads a matrix from a Matrix Market file into sparse storage.
+    ///
+    /// Only the entries listed in the file take memory, so matrices far too large to be stored densely can be read.
+    /// See [`from_matrix_market()`](DataMatrixBuilder::from_matrix_market) for details.
+    pub fn from_matrix_market_sparse<P: AsRef<Path>>(self, filename: P) -> Result<SparseDataMatrix<T>, Error> {
+        let reader = open_file(filename, self.compression)?;
+        let (market, order) = self.collect_matrix_market(reader, true)?;
+        let triplets = market.entries.into_triplets(market.symmetric, None, None);
+        Ok(SparseDataMatrix::from_triplets(triplets, order.row_labels, order.col_labels, self.missing_value))
+    }
+
+    fn read_matrix_market<R: BufRead>(&self, reader: R) -> Result<DataMatrix<T>, Error> {
+        let (market, order) = self.collect_matrix_market(reader, false)?;
+        let (data, missing) = market.entries.into_matrix(market.nrows, market.ncols, market.symmetric, None, None);
+        Ok(DataMatrix::from_vec(data, order.row_labels, order.col_labels)?.with_missing(missing))
+    }
+
+    /// Collects entries of a Matrix Market input, into `sparse` storage if requested, and resolves its labels;
+    /// rows and columns keep the order of the input.
+    fn collect_matrix_market<R: BufRead>(&self, reader: R, sparse: bool) -> Result<(MatrixMarketEntries<T>, Arrangement), Error> {
+        let mut market = read_matrix_market(reader, self.missing_value.clone(), self.duplicates, self.symmetric, sparse)?;
+        let (row_file, col_file) = match &self.label_files {
+            Some((rows, cols)) => (Some(rows.as_path()), Some(cols.as_path())),
+            None => (None, None),
+        };
+        let row_labels = self.matrix_market_labels(market.row_labels.take(), row_file, market.nrows, "row")?;
+        let col_labels = self.matrix_market_labels(market.col_labels.take(), col_file, market.ncols, "col")?;
+        let order = Arrangement {
+            row_order: None,
+            col_order: None,
+            row_labels,
+            col_labels,
+        };
+        Ok((market, order))
+    }
+
+    /// Labels of `n` rows or columns of a Matrix Market input: those stored in the input come first,
+    /// then those of a label file or given to this builder; otherwise labels are generated.
+    fn matrix_market_labels(
+        &self,
+        stored: Option<Vec<String>>,
+        label_file: Option<&Path>,
+        n: usize,
+        prefix: &str,
+    ) -> Result<Vec<String>, Error> {
+        let labels = match (stored, label_file, &self.labels) {
+            (Some(stored), _, _) => stored,
+            (None, Some(path), _) => read_label_file(open_file(path, None)?)?,
+            (None, None, Some(given)) => given.clone(),
+            (None, None, None) => (0..n).map(|k| format!("{prefix}-{}", k + 1)).collect(),
+        };
+        if labels.len() != n {
+            return Err(Error::IncorrectMatrixLabels {
+                expected: labels.len(),
+                actual: n,
+            });
+        }
+        Ok(labels)
     }
 
-    fn read_one_column<P: AsRef<Path>>(
+    fn read_one_column<R: BufRead>(
         &self,
-        filename: P,
+        reader: R,
         column: usize,
         labels: Vec<String>,
-    ) -> Result<DataMatrix, Error> {
-        let rows = parse_plain(filename, ' ', self.skip_header)?;
-        let col_idx = column;
-
+    ) -> Result<DataMatrix<T>, Error> {
         let mut values = Vec::new();
+        let mut missing = Vec::new();
 
-        for (line_num, parts) in rows.into_iter().enumerate() {
-            if col_idx >= parts.len() {
+        for_each_line(reader, self.skip_header, |line_no, line| {
+            let Some(field) = line.split_whitespace().nth(column) else {
                 return Err(Error::NotEnoughColumns {
-                    line: line_num + 1,
-                    needed: col_idx + 1,
-                    content: format!("{:?}", parts),
+                    line: line_no,
+                    needed: column + 1,
+                    content: line.to_string(),
                 });
-            }
-
-            let value: f64 = parts[col_idx].parse().map_err(|_| Error::ParseError {
-                line: line_num + 1,
-                content: parts[col_idx].clone(),
-            })?;
-
-            values.push(value);
-        }
+            };
+            let value = self.parse_value(field, line_no)?;
+            missing.push(value.is_none());
+            values.push(value.unwrap_or_else(|| self.missing_value.clone()));
+            Ok(())
+        })?;
 
         let n = labels.len();
         if n * n != values.len() {
@@ -370,170 +948,312 @@ impl DataMatrixBuilder {
             });
         }
 
-        let mut data = Vec::with_capacity(n);
-        for i in 0..n {
-            let start = i * n;
-            let end = start + n;
-            data.push(values[start..end].to_vec());
+        Ok(DataMatrix::from_vec(values, labels.clone(), labels)?.with_missing(missing))
+    }
+
+    /// Number of fields a line must have to provide all the columns used by this builder.
+    fn columns_needed(&self) -> usize {
+        [self.row_label_col, self.col_label_col, self.data_col]
+            .into_iter()
+            .chain(self.row_idx_col)
+            .chain(self.col_idx_col)
+            .max()
+            .unwrap_or(0)
+            + 1
+    }
+
+    /// Parses a numeric value; returns `None` if the field is one of the NA tokens.
+    fn parse_value(&self, field: &str, line: usize) -> Result<Option<T>, Error> {
+        if self.na_tokens.iter().any(|token| token == field.trim()) {
+            return Ok(None);
         }
+        field.trim().parse().map(Some).map_err(|_| Error::ParseError {
+            line,
+            content: field.to_string(),
+        })
+    }
 
-        DataMatrix::new(data, labels.clone(), labels)
+    /// Reads a wide table: a header line with column labels followed by rows of a label and values.
+    ///
+    /// Line numbers reported in errors are 1-based and count every line of the input.
+    fn read_wide<R: BufRead>(&self, reader: R, separator: char) -> Result<DataMatrix<T>, Error> {
+        let mut header: Option<Vec<String>> = None;
+        let mut row_labels = Vec::new();
+        let mut data = Vec::new();
+        let mut missing = Vec::new();
+        for (line_no, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let parts = split_fields(&line, separator);
+            let Some(col_labels) = &header else {
+                let mut labels: Vec<String> = parts.into_iter().map(|s| s.trim().to_string()).collect();
+                // --- the entry above row labels is either empty or declared with corner_label()
+                if self.corner_label || labels.first().is_some_and(|label| label.is_empty()) {
+                    labels.remove(0);
+                }
+                header = Some(labels);
+                continue;
+            };
+            let ncols = col_labels.len();
+            if parts.len() < ncols + 1 {
+                return Err(Error::NotEnoughColumns {
+                    line: line_no + 1,
+                    needed: ncols + 1,
+                    content: line.clone(),
+                });
+            }
+            if parts.len() > ncols + 1 {
+                return Err(Error::TooManyColumns {
+                    line: line_no + 1,
+                    content: line.clone(),
+                });
+            }
+            for field in &parts[1..] {
+                let value = self.parse_value(field, line_no + 1)?;
+                missing.push(value.is_none());
+                data.push(value.unwrap_or_else(|| self.missing_value.clone()));
+            }
+            row_labels.push(parts[0].trim().to_string());
+        }
+
+        Ok(DataMatrix::from_vec(data, row_labels, header.unwrap_or_default())?.with_missing(missing))
     }
 }
 
-fn parse_plain<P: AsRef<Path>>(
-    filename: P,
-    separator: char,
-    skip_header: bool,
-) -> std::io::Result<Vec<Vec<String>>> {
-    // --- read the file, possibly gzipped
-    let reader = open_file(filename)?;
-
-    let mut first_passed = false;
-    let mut lines = Vec::new();
-    for line in reader.lines() {
-        let line = line?;
-        if line.trim().is_empty() || line.starts_with('#') {
-            continue;
-        }
-        // skip the first line if this is a header
-        if !first_passed && skip_header {
-            first_passed = true;
-            continue;
+/// An entry of the three- or five-column format, as given in a line of the input.
+#[derive(Debug, Clone)]
+struct Entry<T> {
+    line: usize,
+    /// id of the row label
+    row: usize,
+    /// id of the column label
+    col: usize,
+    /// row and column indices, if given explicitly
+    indices: Option<(usize, usize)>,
+    /// `None` for a NA token
+    value: Option<T>,
+}
+
+/// Ids given to row and column labels in the order they are first seen.
+///
+/// Rows and columns of a symmetric matrix share the same ids.
+struct LabelIds {
+    rows: Indexer,
+    cols: Indexer,
+    symmetric: bool,
+}
+
+impl LabelIds {
+    fn new(symmetric: bool) -> Self {
+        Self {
+            rows: Indexer::new(),
+            cols: Indexer::new(),
+            symmetric,
         }
-        let parts: Vec<String> = if separator == ' ' {
-            line.split_whitespace().map(|s| s.to_string()).collect()
+    }
+
+    /// Returns ids of the given row and column labels; labels seen for the first time get new ids.
+    fn add(&mut self, row: &str, col: &str) -> (usize, usize) {
+        let i = self.rows.add(row);
+        let j = if self.symmetric {
+            self.rows.add(col)
         } else {
-            line.split(separator).map(|s| s.to_string()).collect()
+            self.cols.add(col)
         };
-        lines.push(parts);
+        (i, j)
+    }
+
+    fn cols(&self) -> &Indexer {
+        if self.symmetric {
+            &self.rows
+        } else {
+            &self.cols
+        }
     }
-    Ok(lines)
 }
 
-#[derive(Clone)]
-struct Indexer {
-    label_to_index: HashMap<String, usize>,
+/// A matrix being built from entries of the three- or five-column format.
+///
+/// Entries must be added in the order they are given in the input.
+struct LongMatrix<T> {
+    ids: LabelIds,
+    /// indices given explicitly in the input, which define the final order of rows and columns
+    row_indexer: Indexer,
+    col_indexer: Indexer,
+    explicit: bool,
+    entries: Accumulator<T>,
 }
 
-impl Indexer {
-    fn new() -> Self {
+impl<T: Element> LongMatrix<T> {
+    /// Creates an empty matrix; a `sparse` one takes memory only for the entries actually given.
+    fn new(builder: &DataMatrixBuilder<T>, sparse: bool) -> Self {
+        let (fill, policy) = (builder.missing_value.clone(), builder.duplicates);
         Self {
-            label_to_index: HashMap::new(),
+            ids: LabelIds::new(builder.symmetric),
+            row_indexer: Indexer::new(),
+            col_indexer: Indexer::new(),
+            explicit: builder.row_idx_col.is_some() && builder.col_idx_col.is_some(),
+            entries: if sparse {
+                Accumulator::sparse(fill, policy)
+            } else {
+                Accumulator::new(fill, policy)
+            },
         }
     }
 
-    fn add(&mut self, label: &str) -> usize {
-        if let Some(&idx) = self.label_to_index.get(label) {
-            idx
-        } else {
-            let idx = self.label_to_index.len();
-            self.label_to_index.insert(label.to_string(), idx);
-            idx
+    /// Adds an entry which row and column ids have been given by `self.ids`.
+    fn add(&mut self, entry: Entry<T>, row_label: &str, col_label: &str) -> Result<(), Error> {
+        let symmetric = self.ids.symmetric;
+        if let Some((row_idx, col_idx)) = entry.indices {
+            self.row_indexer.add_explicit(row_label, row_idx, entry.line)?;
+            if symmetric {
+                self.row_indexer.add_explicit(col_label, col_idx, entry.line)?;
+            } else {
+                self.col_indexer.add_explicit(col_label, col_idx, entry.line)?;
+            }
         }
+        // --- a NA token leaves the entry missing
+        let Some(value) = entry.value else {
+            return Ok(());
+        };
+        // --- (i, j) and (j, i) of a symmetric matrix are the same entry
+        let (i, j) = if symmetric && entry.col < entry.row {
+            (entry.col, entry.row)
+        } else {
+            (entry.row, entry.col)
+        };
+        self.entries.add(i, j, value, entry.line, || (row_label.to_string(), col_label.to_string()))
     }
 
-    fn add_explicit(&mut self, label: &str, idx: usize) {
-        self.label_to_index.entry(label.to_string()).or_insert(idx);
+    /// Returns the final order of rows and columns, with their labels.
+    fn arrange(&self) -> Result<Arrangement, Error> {
+        let (row_ids, col_ids) = (&self.ids.rows, self.ids.cols());
+        if !self.explicit {
+            return Ok(Arrangement {
+                row_order: None,
+                col_order: None,
+                row_labels: row_ids.to_vec(),
+                col_labels: col_ids.to_vec(),
+            });
+        }
+        let col_indexer = if self.ids.symmetric { &self.row_indexer } else { &self.col_indexer };
+        self.row_indexer.check_contiguous()?;
+        col_indexer.check_contiguous()?;
+        Ok(Arrangement {
+            row_order: Some(positions(row_ids, &self.row_indexer)?),
+            col_order: Some(positions(col_ids, col_indexer)?),
+            row_labels: self.row_indexer.to_vec(),
+            col_labels: col_indexer.to_vec(),
+        })
     }
 
-    fn index(&self, label: &str) -> usize {
-        *self
-            .label_to_index
-            .get(label)
-            .expect("Label not found in indexer")
+    fn finish(self) -> Result<DataMatrix<T>, Error> {
+        let (nrows, ncols) = (self.ids.rows.max_index(), self.ids.cols().max_index());
+        let order = self.arrange()?;
+        let (data, missing) = self.entries.into_matrix(
+            nrows,
+            ncols,
+            self.ids.symmetric,
+            order.row_order.as_deref(),
+            order.col_order.as_deref(),
+        );
+
+        Ok(DataMatrix::from_vec(data, order.row_labels, order.col_labels)?.with_missing(missing))
     }
 
-    fn max_index(&self) -> usize {
-        self.label_to_index.len()
+    /// Turns the entries of a symmetric matrix into its upper triangle, with or without the diagonal.
+    fn finish_packed(self, with_diagonal: bool) -> Result<PackedDataMatrix<T>, Error> {
+        debug_assert!(self.ids.symmetric);
+        let order = self.arrange()?;
+        let (data, missing) = self.entries.into_packed(self.ids.rows.max_index(), with_diagonal, order.row_order.as_deref());
+
+        Ok(PackedDataMatrix::from_vec(data, order.row_labels, with_diagonal)?.with_missing(missing))
     }
 
-    fn to_vec(&self) -> Vec<String> {
-        let mut result = vec!["".to_string(); self.label_to_index.len()];
-        for (label, &idx) in &self.label_to_index {
-            result[idx] = label.clone();
+    /// Turns the entries into a sparse matrix, where entries not given hold the `fill` value.
+    fn finish_sparse(self, fill: T) -> Result<SparseDataMatrix<T>, Error> {
+        let order = self.arrange()?;
+        if order.row_labels.is_empty() {
+            return Err(Error::IncorrectMatrixLabels {
+                expected: 0,
+                actual: 0,
+            });
         }
-        result
+        let triplets =
+            self.entries.into_triplets(self.ids.symmetric, order.row_order.as_deref(), order.col_order.as_deref());
+
+        Ok(SparseDataMatrix::from_triplets(triplets, order.row_labels, order.col_labels, fill))
     }
 }
 
-/// Guess a field separator from the filename extension.
-///
-/// Supported (case-insensitive):
-/// - `csv` → `,`
-/// - `tsv`, `tab` → `\t`
-/// - `psv` (pipe-separated) → `|`
-/// - `ssv` (semicolon-separated) → `;`
-///
-/// Also handles compressed files like `data.csv.gz` (peels one layer).
-///
-/// By default returns ` ` (a space character) if the separator cannot be determined.
-///
-/// # Examples
-/// ```rust,ignore
-/// use std::path::Path;
+/// The final order of rows and columns of a matrix, with their labels.
+struct Arrangement {
+    /// final positions of row and column ids; `None` if these are ordered as first seen
+    row_order: Option<Vec<usize>>,
+    col_order: Option<Vec<usize>>,
+    row_labels: Vec<String>,
+    col_labels: Vec<String>,
+}
+
+/// Calls `f` for every line of the input, except blank lines, comment lines and the header (if requested).
 ///
-/// assert_eq!(guess_separator("data.csv"), ',');
-/// assert_eq!(guess_separator("data.TSV"), '\t');
-/// assert_eq!(guess_separator("table.tab"), '\t');
-/// assert_eq!(guess_separator("log.psv"), '|');
-/// assert_eq!(guess_separator("semi.ssv"), ';');
-/// assert_eq!(guess_separator("archive.csv.gz"), ','); // compressed
-/// ```
-fn guess_separator<P: AsRef<Path>>(path: P) -> char {
-    let path = path.as_ref();
-
-    // Get the likely data extension, handling a single compression suffix.
-    let ext = match path.extension().and_then(|e| e.to_str()) {
-        Some(ext) => {
-            let ext = ext.to_ascii_lowercase();
-            match ext.as_str() {
-                // Peel one compression layer and check the previous extension
-                "gz" | "bz2" | "xz" | "zst" | "zip" => {
-                    // file_stem() of "...csv.gz" is "....csv"
-                    path.file_stem()
-                        .and_then(|s| Path::new(s).extension())
-                        .and_then(|e| e.to_str())
-                        .map(|e| e.to_ascii_lowercase())
-                        .unwrap_or_default()
-                }
-                other => other.to_string(),
-            }
+/// Lines are passed without the line terminator, together with their 1-based line number counted
+/// over all lines of the input. A single buffer is reused for all the lines.
+fn for_each_line<R, F>(mut reader: R, skip_header: bool, mut f: F) -> Result<(), Error>
+where
+    R: BufRead,
+    F: FnMut(usize, &str) -> Result<(), Error>,
+{
+    let mut buffer = String::new();
+    let mut line_no = 0;
+    let mut header_skipped = !skip_header;
+    loop {
+        buffer.clear();
+        if reader.read_line(&mut buffer)? == 0 {
+            return Ok(());
         }
-        None => String::new(),
-    };
+        line_no += 1;
+        let Some(line) = data_line(&buffer) else {
+            continue;
+        };
+        if !header_skipped {
+            header_skipped = true;
+            continue;
+        }
+        f(line_no, line)?;
+    }
+}
 
-    match ext.as_str() {
-        "dat" => ' ',
-        "csv" => ',',
-        "tsv" | "tab" => '\t',
-        "psv" => '|',
-        "ssv" => ';',
-        _ => ' ',
+/// Strips the line terminator; returns `None` for a blank line or a comment line.
+fn data_line(line: &str) -> Option<&str> {
+    let line = line.trim_end_matches(['\n', '\r']);
+    if line.trim().is_empty() || line.starts_with('#') {
+        None
+    } else {
+        Some(line)
     }
 }
 
-/// This function can open a regular file or a gzipped one, as determined by the extension
-/// of the input file name. A boxed reader to the content is returned.
-///
-/// The code has been copied from bioshell-io::utils
-fn open_file<P: AsRef<Path>>(file_path: P) -> io::Result<Box<dyn BufRead>> {
-    let path = file_path.as_ref();
-
-    if path.as_os_str().is_empty() {
-        return Err(io::Error::new(
-            ErrorKind::InvalidInput,
-            "Couldn't open file: empty path",
-        ));
-    }
-    let file = File::open(path)?;
-
-    if file_path.as_ref().extension() == Some(OsStr::new("gz")) {
-        Ok(Box::new(BufReader::with_capacity(
-            128 * 1024,
-            read::GzDecoder::new(file),
-        )))
+/// Parses a 0-based row or column index given explicitly in the input.
+fn parse_index(field: &str, line: usize) -> Result<usize, Error> {
+    field.trim().parse().map_err(|_| Error::ParseError {
+        line,
+        content: field.to_string(),
+    })
+}
+
+/// Returns the final position of every label id, as given by an indexer of explicit indices.
+fn positions(ids: &Indexer, indexer: &Indexer) -> Result<Vec<usize>, Error> {
+    ids.to_vec().iter().map(|label| indexer.get_or_err(label)).collect()
+}
+
+/// Splits a line into fields; `' '` separator splits by any white space.
+fn split_fields(line: &str, separator: char) -> Vec<&str> {
+    if separator == ' ' {
+        line.split_whitespace().collect()
     } else {
-        Ok(Box::new(BufReader::with_capacity(128 * 1024, file)))
+        line.split(separator).collect()
     }
 }
@@ -0,0 +1,146 @@
+//! Parallel parsing of the three- and five-column formats.
+//!
+//! The input is read in blocks of whole lines. Each block is split on line boundaries into chunks,
+//! which are parsed concurrently: every chunk gives ids to its labels on its own. Chunks are then merged
+//! one by one in the order of the input, translating their label ids into the ids of the whole matrix,
+//! so labels are numbered as if the input was read sequentially.
+use std::io::{self, BufRead, Read};
+
+use rayon::prelude::*;
+
+use super::{data_line, DataMatrixBuilder, Entry, LabelIds, LongMatrix};
+use crate::{Element, Error};
+
+/// Number of bytes read from the input at once
+const BLOCK_SIZE: usize = 4 * 1024 * 1024;
+
+/// Entries parsed from a chunk of the input, up to the first error (if any)
+struct Chunk<T> {
+    ids: LabelIds,
+    entries: Vec<Entry<T>>,
+    error: Option<Error>,
+}
+
+impl<T: Element> DataMatrixBuilder<T> {
+    /// Collects entries of the three- or five-column format with all the threads of the `rayon` pool.
+    pub(super) fn collect_long_parallel<R: BufRead>(
+        &self,
+        mut reader: R,
+        separator: char,
+        sparse: bool,
+    ) -> Result<LongMatrix<T>, Error> {
+        let mut matrix = LongMatrix::new(self, sparse);
+        // --- number of lines read so far
+        let mut line_no = 0;
+        if self.skip_header {
+            let mut header = String::new();
+            loop {
+                header.clear();
+                if reader.read_line(&mut header)? == 0 {
+                    break;
+                }
+                line_no += 1;
+                if data_line(&header).is_some() {
+                    break;
+                }
+            }
+        }
+
+        let n_chunks = 4 * rayon::current_num_threads();
+        let mut block = Vec::with_capacity(BLOCK_SIZE);
+        loop {
+            block.clear();
+            (&mut reader).take(BLOCK_SIZE as u64).read_to_end(&mut block)?;
+            // --- complete the last line of a block
+            reader.read_until(b'\n', &mut block)?;
+            if block.is_empty() {
+                break;
+            }
+            let pieces = split_lines(&block, n_chunks);
+            let mut first_lines = Vec::with_capacity(pieces.len());
+            for piece in &pieces {
+                first_lines.push(line_no + 1);
+                line_no += piece.iter().filter(|&&b| b == b'\n').count();
+            }
+            let chunks: Vec<Chunk<T>> = pieces
+                .par_iter()
+                .zip(first_lines)
+                .map(|(piece, first_line)| self.parse_chunk(piece, first_line, separator))
+                .collect();
+            for chunk in chunks {
+                merge_chunk(&mut matrix, chunk)?;
+            }
+        }
+        Ok(matrix)
+    }
+
+    /// Parses lines of a chunk, which starts at the given line of the input.
+    fn parse_chunk(&self, bytes: &[u8], first_line: usize, separator: char) -> Chunk<T> {
+        let mut chunk = Chunk {
+            ids: LabelIds::new(self.symmetric),
+            entries: Vec::new(),
+            error: None,
+        };
+        for (line_no, raw) in (first_line..).zip(bytes.split(|&b| b == b'\n')) {
+            let Ok(line) = std::str::from_utf8(raw) else {
+                let error = io::Error::new(io::ErrorKind::InvalidData, "stream did not contain valid UTF-8");
+                chunk.error = Some(error.into());
+                break;
+            };
+            let Some(line) = data_line(line) else {
+                continue;
+            };
+            match self.parse_entry(line_no, line, separator, &mut chunk.ids) {
+                Ok((entry, _, _)) => chunk.entries.push(entry),
+                Err(error) => {
+                    chunk.error = Some(error);
+                    break;
+                }
+            }
+        }
+        chunk
+    }
+}
+
+/// Adds entries of a chunk to the matrix, then returns the error the chunk ended with, if any.
+fn merge_chunk<T: Element>(matrix: &mut LongMatrix<T>, chunk: Chunk<T>) -> Result<(), Error> {
+    let row_labels = chunk.ids.rows.to_vec();
+    let col_labels = chunk.ids.cols().to_vec();
+    // --- labels given ids in the chunk are registered in the order they have been seen there
+    let row_ids: Vec<usize> = row_labels.iter().map(|label| matrix.ids.rows.add(label)).collect();
+    let col_ids: Vec<usize> = if chunk.ids.symmetric {
+        row_ids.clone()
+    } else {
+        col_labels.iter().map(|label| matrix.ids.cols.add(label)).collect()
+    };
+    for entry in chunk.entries {
+        let (row, col) = (entry.row, entry.col);
+        let global = Entry {
+            row: row_ids[row],
+            col: col_ids[col],
+            ..entry
+        };
+        matrix.add(global, &row_labels[row], &col_labels[col])?;
+    }
+    match chunk.error {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+/// Splits text into at most about `n` pieces, each made of whole lines.
+fn split_lines(text: &[u8], n: usize) -> Vec<&[u8]> {
+    let size = text.len() / n + 1;
+    let mut pieces = Vec::with_capacity(n + 1);
+    let mut rest = text;
+    while !rest.is_empty() {
+        let end = match rest.iter().skip(size).position(|&b| b == b'\n') {
+            Some(pos) => size + pos + 1,
+            None => rest.len(),
+        };
+        let (piece, tail) = rest.split_at(end);
+        pieces.push(piece);
+        rest = tail;
+    }
+    pieces
+}
@@ -0,0 +1,301 @@
+use std::collections::HashSet;
+
+use crate::indexer::Indexer;
+use crate::{DataMatrix, Error};
+
+/// A borrowed view of a [`DataMatrix`]: a selection of its rows and columns, possibly reordered
+/// or transposed, which does not copy any value.
+///
+/// A view maps each of its rows and columns to a row or a column of the underlying matrix; only
+/// these index maps are allocated. Views are created with [`DataMatrix::view()`] and refined with
+/// [`select()`](DataMatrixView::select), [`select_indices()`](DataMatrixView::select_indices)
+/// and [`transpose()`](DataMatrixView::transpose); [`to_owned()`](DataMatrixView::to_owned) copies
+/// the viewed entries into a new [`DataMatrix`].
+///
+/// # Example
+/// ```rust
+/// use data_matrix::{DataMatrixBuilder, Error};
+/// # fn main() -> Result<(), Error> {
+/// let matrix = DataMatrixBuilder::new().from_str("A X 1.0\nA Y 2.0\nB X 3.0\nB Y 4.0\nC X 5.0\nC Y 6.0\n")?;
+/// let view = matrix.view().select(&["C", "A"], &["Y"])?.transpose();
+/// assert_eq!(view.shape(), (1, 2));
+/// assert_eq!(view.row_labels(), ["Y"]);
+/// assert_eq!(view.col_labels(), ["C", "A"]);
+/// assert_eq!(view.get_by_label("Y", "A"), Some(2.0));
+///
+/// let copy = view.to_owned();
+/// assert_eq!(copy.as_slice(), &[6.0, 2.0]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DataMatrixView<'a, T = f64> {
+    /// The viewed matrix.
+    matrix: &'a DataMatrix<T>,
+
+    /// Rows of the view (index -> index of a row of the matrix, or of a column when transposed).
+    rows: Vec<usize>,
+
+    /// Columns of the view (index -> index of a column of the matrix, or of a row when transposed).
+    cols: Vec<usize>,
+
+    /// Index of a row of the matrix (a column when transposed) -> row of the view, if selected.
+    row_positions: Vec<Option<usize>>,
+
+    /// Index of a column of the matrix (a row when transposed) -> column of the view, if selected.
+    col_positions: Vec<Option<usize>>,
+
+    /// Whether rows of the view are columns of the matrix.
+    transposed: bool,
+}
+
+impl<'a, T> DataMatrixView<'a, T> {
+    /// Creates a view of all the rows and columns of a matrix.
+    pub(crate) fn new(matrix: &'a DataMatrix<T>) -> Self {
+        let rows: Vec<usize> = (0..matrix.nrows()).collect();
+        let cols: Vec<usize> = (0..matrix.ncols()).collect();
+        Self {
+            matrix,
+            row_positions: rows.iter().copied().map(Some).collect(),
+            col_positions: cols.iter().copied().map(Some).collect(),
+            rows,
+            cols,
+            transposed: false,
+        }
+    }
+
+    /// Creates a view of the given rows and columns of this view, in the order of the given labels.
+    ///
+    /// Returns [`Error::UnknownLabel`] for a label this view doesn't have, [`Error::DuplicateLabel`]
+    /// if a label is given twice and [`Error::EmptySelection`] if no row or no column is given.
+    pub fn select(&self, rows: &[&str], cols: &[&str]) -> Result<Self, Error> {
+        let unknown = |label: &&str| Error::UnknownLabel {
+            label: label.to_string(),
+        };
+        let rows: Vec<usize> = rows.iter().map(|label| self.row_index(label).ok_or_else(|| unknown(label))).collect::<Result<_, _>>()?;
+        let cols: Vec<usize> = cols.iter().map(|label| self.col_index(label).ok_or_else(|| unknown(label))).collect::<Result<_, _>>()?;
+        self.select_indices(&rows, &cols)
+    }
+
+    /// Creates a view of the given rows and columns of this view, in the given order.
+    ///
+    /// Returns [`Error::IndexOutOfBounds`] for an index out of the shape of this view,
+    /// [`Error::DuplicateLabel`] if an index is given twice and [`Error::EmptySelection`]
+    /// if no row or no column is given.
+    pub fn select_indices(&self, rows: &[usize], cols: &[usize]) -> Result<Self, Error> {
+        if rows.is_empty() || cols.is_empty() {
+            return Err(Error::EmptySelection {
+                nrows: rows.len(),
+                ncols: cols.len(),
+            });
+        }
+        let out_of_bounds = |row, col| Error::IndexOutOfBounds {
+            row,
+            col,
+            nrows: self.nrows(),
+            ncols: self.ncols(),
+        };
+        if let Some(&i) = rows.iter().find(|&&i| i >= self.nrows()) {
+            return Err(out_of_bounds(i, cols[0]));
+        }
+        if let Some(&j) = cols.iter().find(|&&j| j >= self.ncols()) {
+            return Err(out_of_bounds(rows[0], j));
+        }
+        check_unique(rows, |i| self.row_label(i))?;
+        check_unique(cols, |j| self.col_label(j))?;
+
+        let rows: Vec<usize> = rows.iter().map(|&i| self.rows[i]).collect();
+        let cols: Vec<usize> = cols.iter().map(|&j| self.cols[j]).collect();
+        Ok(Self {
+            matrix: self.matrix,
+            row_positions: positions(&rows, self.row_positions.len()),
+            col_positions: positions(&cols, self.col_positions.len()),
+            rows,
+            cols,
+            transposed: self.transposed,
+        })
+    }
+
+    /// Swaps rows and columns of this view.
+    pub fn transpose(self) -> Self {
+        Self {
+            matrix: self.matrix,
+            rows: self.cols,
+            cols: self.rows,
+            row_positions: self.col_positions,
+            col_positions: self.row_positions,
+            transposed: !self.transposed,
+        }
+    }
+
+    /// Returns the number of rows.
+    pub fn nrows(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns the number of columns.
+    pub fn ncols(&self) -> usize {
+        self.cols.len()
+    }
+
+    /// Returns the shape of the view as (rows, columns).
+    pub fn shape(&self) -> (usize, usize) {
+        (self.nrows(), self.ncols())
+    }
+
+    /// Checks if the view is square.
+    pub fn is_square(&self) -> bool {
+        self.nrows() == self.ncols()
+    }
+
+    /// Gets the entry at (i, j) of this view.
+    pub fn get(&self, i: usize, j: usize) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.get_ref(i, j).cloned()
+    }
+
+    /// Gets a reference to the entry at (i, j) of this view.
+    pub fn get_ref(&self, i: usize, j: usize) -> Option<&'a T> {
+        let (row, col) = self.source(i, j)?;
+        self.matrix.get_ref(row, col)
+    }
+
+    /// Gets the entry by row and column label.
+    pub fn get_by_label(&self, row_label: &str, col_label: &str) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.get(self.row_index(row_label)?, self.col_index(col_label)?)
+    }
+
+    /// Returns the index of a row of this view by its label.
+    pub fn row_index(&self, label: &str) -> Option<usize> {
+        let k = if self.transposed {
+            self.matrix.col_index(label)
+        } else {
+            self.matrix.row_index(label)
+        };
+        self.row_positions[k?]
+    }
+
+    /// Returns the index of a column of this view by its label.
+    pub fn col_index(&self, label: &str) -> Option<usize> {
+        let k = if self.transposed {
+            self.matrix.row_index(label)
+        } else {
+            self.matrix.col_index(label)
+        };
+        self.col_positions[k?]
+    }
+
+    /// Returns the label of a row by its index.
+    pub fn row_label(&self, index: usize) -> &'a String {
+        let k = self.rows[index];
+        if self.transposed {
+            self.matrix.col_label(k)
+        } else {
+            self.matrix.row_label(k)
+        }
+    }
+
+    /// Returns the label of a column by its index.
+    pub fn col_label(&self, index: usize) -> &'a String {
+        let k = self.cols[index];
+        if self.transposed {
+            self.matrix.row_label(k)
+        } else {
+            self.matrix.col_label(k)
+        }
+    }
+
+    /// Returns the row labels, in the order of the rows of this view.
+    pub fn row_labels(&self) -> Vec<&'a str> {
+        (0..self.nrows()).map(|i| self.row_label(i).as_str()).collect()
+    }
+
+    /// Returns the column labels, in the order of the columns of this view.
+    pub fn col_labels(&self) -> Vec<&'a str> {
+        (0..self.ncols()).map(|j| self.col_label(j).as_str()).collect()
+    }
+
+    /// Iterates over the entries of the i-th row of this view.
+    ///
+    /// # Panics
+    /// Panics if `i` is out of bounds.
+    pub fn row(&self, i: usize) -> impl Iterator<Item = &'a T> + '_ {
+        assert!(i < self.nrows(), "row {i} out of bounds of a view of {} rows", self.nrows());
+        (0..self.ncols()).filter_map(move |j| self.get_ref(i, j))
+    }
+
+    /// Iterates over the rows of this view.
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = &'a T> + '_> + '_ {
+        (0..self.nrows()).map(move |i| self.row(i))
+    }
+
+    /// Iterates over all the entries of this view, row by row, as (row, column, value) triplets.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, &'a T)> + '_ {
+        (0..self.nrows()).flat_map(move |i| (0..self.ncols()).filter_map(move |j| Some((i, j, self.get_ref(i, j)?))))
+    }
+
+    /// Checks whether the entry at (i, j) is missing; see [`DataMatrix::is_missing()`].
+    pub fn is_missing(&self, i: usize, j: usize) -> bool {
+        self.source(i, j).is_some_and(|(row, col)| self.matrix.is_missing(row, col))
+    }
+
+    /// Counts the missing entries of this view.
+    pub fn count_missing(&self) -> usize {
+        (0..self.nrows())
+            .map(|i| (0..self.ncols()).filter(|&j| self.is_missing(i, j)).count())
+            .sum()
+    }
+
+    /// Copies the entries of this view into a new matrix, with the labels of the view.
+    pub fn to_owned(&self) -> DataMatrix<T>
+    where
+        T: Clone,
+    {
+        let data = self.iter().map(|(_, _, value)| value.clone()).collect();
+        let missing = (0..self.nrows())
+            .flat_map(|i| (0..self.ncols()).map(move |j| self.is_missing(i, j)))
+            .collect();
+        let row_labels: Vec<String> = (0..self.nrows()).map(|i| self.row_label(i).clone()).collect();
+        let col_labels: Vec<String> = (0..self.ncols()).map(|j| self.col_label(j).clone()).collect();
+        DataMatrix {
+            data,
+            nrows: self.nrows(),
+            ncols: self.ncols(),
+            row_index: Indexer::from_labels(&row_labels),
+            col_index: Indexer::from_labels(&col_labels),
+            row_labels,
+            col_labels,
+            missing: None,
+        }
+        .with_missing(missing)
+    }
+
+    /// Row and column of the matrix holding the entry (i, j) of this view.
+    fn source(&self, i: usize, j: usize) -> Option<(usize, usize)> {
+        let (&row, &col) = (self.rows.get(i)?, self.cols.get(j)?);
+        Some(if self.transposed { (col, row) } else { (row, col) })
+    }
+}
+
+/// Positions of the selected indices among `n` rows or columns.
+fn positions(selected: &[usize], n: usize) -> Vec<Option<usize>> {
+    let mut positions = vec![None; n];
+    for (position, &k) in selected.iter().enumerate() {
+        positions[k] = Some(position);
+    }
+    positions
+}
+
+/// Returns [`Error::DuplicateLabel`] for the label of an index given twice.
+fn check_unique<'a>(indices: &[usize], label: impl Fn(usize) -> &'a String) -> Result<(), Error> {
+    let mut seen = HashSet::with_capacity(indices.len());
+    match indices.iter().find(|&&k| !seen.insert(k)) {
+        Some(&k) => Err(Error::DuplicateLabel { label: label(k).clone() }),
+        None => Ok(()),
+    }
+}
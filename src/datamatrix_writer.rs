@@ -0,0 +1,292 @@
+use std::fmt::Display;
+use std::io::Write;
+use std::path::Path;
+
+use crate::io_utils::{guess_separator, write_file};
+use crate::matrix_market::write_matrix_market;
+use crate::phylip::write_phylip;
+use crate::{DataMatrix, Element, Error};
+
+/// Text layouts that [`DataMatrixWriter`] can produce.
+///
+/// Each layout corresponds to an input format understood by [`DataMatrixBuilder`](crate::DataMatrixBuilder),
+/// so a written file can be loaded back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// One entry per line: row label, column label, value
+    ThreeColumns,
+    /// One entry per line: row label, column label, row index, column index, value
+    FiveColumns,
+    /// All values of the matrix, one per line, packed row-wise; labels are not stored
+    SingleColumn,
+    /// Dense table: a header line with column labels, then a row label followed by values in each line
+    Wide,
+    /// PHYLIP distance matrix: the number of taxa, then a taxon name followed by distances in each line
+    ///
+    /// `strict` names are padded to exactly 10 characters; `lower_triangle` writes for each taxon
+    /// only the distances to the preceding taxa.
+    Phylip { strict: bool, lower_triangle: bool },
+    /// Matrix Market coordinate format: a size line, then 1-based row and column indices followed by a value
+    /// in each line; labels are stored in `%` comments
+    ///
+    /// A `symmetric` matrix is written with the `symmetric` qualifier, i.e. only its lower triangle;
+    /// [`Error::NotSymmetric`] is returned unless the matrix is indeed symmetric.
+    MatrixMarket { symmetric: bool },
+}
+
+/// A writer that saves a [`DataMatrix`] as a text file.
+///
+/// [`DataMatrixWriter`] is configured in the same fashion as [`DataMatrixBuilder`](crate::DataMatrixBuilder):
+/// - choose the output [`Layout`],
+/// - control the separator (space, comma, tab, etc.),
+/// - optionally write a header line and `#` comment lines,
+/// - write only the upper triangle of a symmetric matrix,
+/// - choose the token written for missing entries.
+///
+/// Files which name ends with `.gz` are gzip-compressed; `.bz2`, `.xz` and `.zst` files are compressed
+/// as well when the respective cargo feature is enabled (see [`Compression`](crate::Compression)).
+///
+/// # Example
+/// ```rust
+/// use data_matrix::{DataMatrixBuilder, DataMatrixWriter, Error, Layout};
+/// # fn main() -> Result<(), Error> {
+/// # let input_fname = "./tests/test_files/five_columns_short.txt";
+/// let matrix = DataMatrixBuilder::new()
+///     .index_columns(2, 3)
+///     .data_column(4)
+///     .symmetric(true)
+///     .from_file(input_fname)?;
+/// # let output = std::env::temp_dir().join(format!("datamatrix_writer_doctest_{}.csv", std::process::id()));
+/// DataMatrixWriter::new()
+///     .layout(Layout::ThreeColumns)
+///     .separator(',')             // if not given, inferred from the file extension
+///     .header(true)               // write "row_label,col_label,value" line first
+///     .comment("Written by data-matrix")
+///     .upper_triangle(true)       // (i, j) entries where i <= j only
+///     .to_file(&matrix, &output)?;
+/// let copy = DataMatrixBuilder::new()
+///     .symmetric(true)
+///     .skip_header(true)
+///     .from_file(&output)?;
+/// assert_eq!(copy.get_by_label("John", "Bob"), Some(2.2));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Notes
+/// - when `' '` (a space) is used a separator, labels must not contain white spaces, otherwise the file
+///   can't be read back
+/// - [`upper_triangle()`](DataMatrixWriter::upper_triangle) requires a square matrix and has no effect
+///   on the [`Layout::SingleColumn`] and [`Layout::Wide`] layouts, which always hold all the values
+/// - [`Layout::Wide`] files always start with the line of column labels, regardless of
+///   [`header()`](DataMatrixWriter::header); read them with [`from_wide_file()`](crate::DataMatrixBuilder::from_wide_file)
+/// - [`Layout::Phylip`] ignores the separator, header and upper triangle settings; comments are not
+///   allowed by the format and are not written
+/// - [`Layout::MatrixMarket`] ignores the separator, header and upper triangle settings; values are declared
+///   as `integer` or `real` according to their type, labels must not contain white spaces and missing entries
+///   are skipped; [`Error::UnsupportedElementType`] is returned for values which are not numbers
+/// - [missing entries](DataMatrix::is_missing) are written as the [NA token](DataMatrixWriter::na_token)
+///   by the three-, five- and single-column and wide layouts, so that rows and columns holding only missing
+///   entries are kept, and written as their fill values in PHYLIP files
+#[derive(Debug, Clone)]
+pub struct DataMatrixWriter {
+    layout: Layout,
+    separator: Option<char>,
+    header: bool,
+    comments: Vec<String>,
+    upper_triangle: bool,
+    na_token: String,
+}
+
+#[allow(clippy::new_without_default)]
+impl DataMatrixWriter {
+    /// Creates a new writer that produces the three-column layout.
+    pub fn new() -> Self {
+        Self {
+            layout: Layout::ThreeColumns,
+            separator: None,
+            header: false,
+            comments: Vec::new(),
+            upper_triangle: false,
+            na_token: "NA".to_string(),
+        }
+    }
+
+    /// Sets the layout of the output file.
+    pub fn layout(mut self, layout: Layout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Sets the character used to separate fields in the output file.
+    ///
+    /// If not given, the separator is inferred from the file extension, e.g. `','` for `.csv`
+    /// or `'\t'` for `.tsv`; `' '` is used otherwise.
+    pub fn separator(mut self, sep: char) -> Self {
+        self.separator = Some(sep);
+        self
+    }
+
+    /// If set to `true`, a header line with column names is written before the data.
+    ///
+    /// Use [`skip_header(true)`](crate::DataMatrixBuilder::skip_header) to read such a file back.
+    pub fn header(mut self, if_header: bool) -> Self {
+        self.header = if_header;
+        self
+    }
+
+    /// Adds a comment to the preamble of the output file.
+    ///
+    /// Every line of the text is written with a `#` prefix, before the header and the data.
+    pub fn comment<S: Into<String>>(mut self, text: S) -> Self {
+        let text: String = text.into();
+        self.comments.extend(text.lines().map(|l| l.to_string()));
+        self
+    }
+
+    /// If set to `true`, only entries `(i, j)` where `i <= j` are written.
+    ///
+    /// This is devised for symmetric matrices, which can be restored with
+    /// [`symmetric(true)`](crate::DataMatrixBuilder::symmetric).
+    pub fn upper_triangle(mut self, if_upper: bool) -> Self {
+        self.upper_triangle = if_upper;
+        self
+    }
+
+    /// Sets the token written in place of missing entries; `"NA"` by default.
+    ///
+    /// The token should be recognised by [`na_tokens()`](crate::DataMatrixBuilder::na_tokens) of the builder
+    /// that reads the file back.
+    pub fn na_token<S: Into<String>>(mut self, token: S) -> Self {
+        self.na_token = token.into();
+        self
+    }
+
+    /// Writes the matrix into a file according to the current writer settings.
+    ///
+    /// The output is compressed when the file name ends with `.gz`, `.bz2`, `.xz` or `.zst`.
+    pub fn to_file<T: Element, P: AsRef<Path>>(&self, matrix: &DataMatrix<T>, filename: P) -> Result<(), Error> {
+        let separator = match self.separator {
+            None => guess_separator(&filename),
+            Some(c) => c,
+        };
+        write_file(filename, |writer| self.write(matrix, writer, separator))
+    }
+
+    /// Writes row and column labels of a matrix into two companion files, one label per line.
+    ///
+    /// Such files accompany matrices exchanged in formats which hold no labels, e.g. Matrix Market files
+    /// read by other tools; they are read back with [`label_files()`](crate::DataMatrixBuilder::label_files).
+    /// A file is compressed when its name ends with `.gz`, `.bz2`, `.xz` or `.zst`.
+    pub fn to_label_files<T, P: AsRef<Path>, Q: AsRef<Path>>(&self, matrix: &DataMatrix<T>, row_labels: P, col_labels: Q) -> Result<(), Error> {
+        for (path, labels) in [(row_labels.as_ref(), matrix.row_labels()), (col_labels.as_ref(), matrix.col_labels())] {
+            write_file(path, |writer| {
+                for label in labels {
+                    writeln!(writer, "{}", label)?;
+                }
+                Ok(())
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Writes the matrix into any [`Write`] implementation, e.g. a `Vec<u8>` buffer or `stdout`.
+    ///
+    /// When the separator hasn't been set, `' '` is used.
+    pub fn to_writer<T: Element, W: Write>(&self, matrix: &DataMatrix<T>, mut writer: W) -> Result<(), Error> {
+        self.write(matrix, &mut writer, self.separator.unwrap_or(' '))
+    }
+
+    fn write<T: Element>(&self, matrix: &DataMatrix<T>, writer: &mut dyn Write, sep: char) -> Result<(), Error> {
+        let long_layout = matches!(self.layout, Layout::ThreeColumns | Layout::FiveColumns);
+        if self.upper_triangle && long_layout && !matrix.is_square() {
+            return Err(Error::NotSquare {
+                nrows: matrix.nrows(),
+                ncols: matrix.ncols(),
+            });
+        }
+        if let Layout::Phylip { strict, lower_triangle } = self.layout {
+            return write_phylip(matrix, writer, strict, lower_triangle);
+        }
+        if let Layout::MatrixMarket { symmetric } = self.layout {
+            return write_matrix_market(matrix, writer, symmetric, &self.comments);
+        }
+        for line in &self.comments {
+            writeln!(writer, "# {}", line)?;
+        }
+
+        match self.layout {
+            Layout::ThreeColumns => {
+                if self.header {
+                    writeln!(writer, "row_label{sep}col_label{sep}value")?;
+                }
+                for (i, j) in self.entries(matrix) {
+                    writeln!(
+                        writer,
+                        "{}{sep}{}{sep}{}",
+                        matrix.row_label(i),
+                        matrix.col_label(j),
+                        self.format_value(matrix, i, j)
+                    )?;
+                }
+            }
+            Layout::FiveColumns => {
+                if self.header {
+                    writeln!(writer, "row_label{sep}col_label{sep}row_index{sep}col_index{sep}value")?;
+                }
+                for (i, j) in self.entries(matrix) {
+                    writeln!(
+                        writer,
+                        "{}{sep}{}{sep}{i}{sep}{j}{sep}{}",
+                        matrix.row_label(i),
+                        matrix.col_label(j),
+                        self.format_value(matrix, i, j)
+                    )?;
+                }
+            }
+            Layout::SingleColumn => {
+                if self.header {
+                    writeln!(writer, "value")?;
+                }
+                for i in 0..matrix.nrows() {
+                    for j in 0..matrix.ncols() {
+                        writeln!(writer, "{}", self.format_value(matrix, i, j))?;
+                    }
+                }
+            }
+            Layout::Wide => {
+                // --- an empty leading entry above row labels would vanish when splitting by white spaces
+                if sep != ' ' {
+                    write!(writer, "{sep}")?;
+                }
+                writeln!(writer, "{}", matrix.col_labels().join(&sep.to_string()))?;
+                for i in 0..matrix.nrows() {
+                    write!(writer, "{}", matrix.row_label(i))?;
+                    for j in 0..matrix.ncols() {
+                        write!(writer, "{sep}{}", self.format_value(matrix, i, j))?;
+                    }
+                    writeln!(writer)?;
+                }
+            }
+            Layout::Phylip { .. } => unreachable!("PHYLIP output is written by write_phylip()"),
+            Layout::MatrixMarket { .. } => unreachable!("Matrix Market output is written by write_matrix_market()"),
+        }
+        Ok(())
+    }
+
+    /// Iterates over `(row, column)` index pairs to be written, row by row.
+    fn entries<'a, T>(&self, matrix: &'a DataMatrix<T>) -> impl Iterator<Item = (usize, usize)> + 'a {
+        let ncols = matrix.ncols();
+        let upper = self.upper_triangle;
+        (0..matrix.nrows()).flat_map(move |i| (if upper { i } else { 0 }..ncols).map(move |j| (i, j)))
+    }
+
+    /// Formats the entry at (i, j), using the NA token for a missing entry.
+    fn format_value<T: Display>(&self, matrix: &DataMatrix<T>, i: usize, j: usize) -> String {
+        if matrix.is_missing(i, j) {
+            self.na_token.clone()
+        } else {
+            matrix.row(i)[j].to_string()
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Types of values stored in a [`DataMatrix`](crate::DataMatrix) loaded or written by this crate.
+///
+/// Values are parsed from text with [`FromStr`] and written with [`Display`]; [`Default`] provides
+/// the value of missing entries unless [`missing_value()`](crate::DataMatrixBuilder::missing_value) says otherwise.
+///
+/// The provided methods support the aggregating [duplicate policies](crate::DuplicatePolicy):
+/// they return `None` unless the type implements them. Numeric types implement all of them;
+/// `bool`, `char` and `String` are stored as they are, so only the policies which keep one of the values
+/// can resolve their duplicates.
+///
+/// # Example
+/// ```rust
+/// use data_matrix::{DataMatrixBuilder, Error};
+/// # fn main() -> Result<(), Error> {
+/// let matrix = DataMatrixBuilder::new()
+///     .element_type::<u32>()
+///     .from_str("A B 12\nB C 7\n")?;
+/// assert_eq!(matrix.get_by_label("A", "B"), Some(12));
+/// assert_eq!(matrix.get_by_label("A", "C"), Some(0));
+/// # Ok(())
+/// # }
+/// ```
+pub trait Element: Clone + Default + PartialEq + FromStr + Display + Send + Sync + 'static {
+    /// Field of the values in the header of a Matrix Market file, `real` or `integer`; `None` for types
+    /// the format can't hold.
+    const MATRIX_MARKET_FIELD: Option<&'static str> = None;
+
+    /// Returns the sum of two values, used by [`DuplicatePolicy::Sum`](crate::DuplicatePolicy::Sum)
+    /// and [`DuplicatePolicy::Average`](crate::DuplicatePolicy::Average); `None` if the sum overflows.
+    fn sum(&self, _other: &Self) -> Option<Self> {
+        None
+    }
+
+    /// Returns the smaller of two values, used by [`DuplicatePolicy::Min`](crate::DuplicatePolicy::Min).
+    fn smaller(&self, _other: &Self) -> Option<Self> {
+        None
+    }
+
+    /// Returns the larger of two values, used by [`DuplicatePolicy::Max`](crate::DuplicatePolicy::Max).
+    fn larger(&self, _other: &Self) -> Option<Self> {
+        None
+    }
+
+    /// Divides a sum of `n` values by `n`, used by [`DuplicatePolicy::Average`](crate::DuplicatePolicy::Average).
+    fn mean(&self, _n: usize) -> Option<Self> {
+        None
+    }
+}
+
+macro_rules! float_element {
+    ($($t:ty),*) => {$(
+        impl Element for $t {
+            const MATRIX_MARKET_FIELD: Option<&'static str> = Some("real");
+
+            fn sum(&self, other: &Self) -> Option<Self> {
+                Some(self + other)
+            }
+            fn smaller(&self, other: &Self) -> Option<Self> {
+                Some(self.min(*other))
+            }
+            fn larger(&self, other: &Self) -> Option<Self> {
+                Some(self.max(*other))
+            }
+            fn mean(&self, n: usize) -> Option<Self> {
+                Some(self / n as $t)
+            }
+        }
+    )*};
+}
+
+// --- sums which overflow give `None`; means are rounded towards zero
+macro_rules! integer_element {
+    ($($t:ty),*) => {$(
+        impl Element for $t {
+            const MATRIX_MARKET_FIELD: Option<&'static str> = Some("integer");
+
+            fn sum(&self, other: &Self) -> Option<Self> {
+                self.checked_add(*other)
+            }
+            fn smaller(&self, other: &Self) -> Option<Self> {
+                Some(*self.min(other))
+            }
+            fn larger(&self, other: &Self) -> Option<Self> {
+                Some(*self.max(other))
+            }
+            fn mean(&self, n: usize) -> Option<Self> {
+                match <$t>::try_from(n) {
+                    Ok(n) => self.checked_div(n),
+                    // --- `n` is out of the range of a narrow type, which fits in i128 anyway
+                    Err(_) => <$t>::try_from(i128::try_from(*self).ok()? / n as i128).ok(),
+                }
+            }
+        }
+    )*};
+}
+
+float_element!(f32, f64);
+integer_element!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl Element for bool {}
+impl Element for char {}
+impl Element for String {}
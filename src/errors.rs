@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use crate::DuplicatePolicy;
+
 /// Custom error type for DataMatrix operations.
 #[derive(Debug, Error)]
 #[non_exhaustive]
@@ -16,8 +18,8 @@ pub enum Error {
         content: String,
     },
 
-    /// More than one column found when expecting single column input.
-    #[error("Line {line} has too many columns when expecting single value: '{content}'")]
+    /// Line has more columns than expected.
+    #[error("Line {line} has too many columns: '{content}'")]
     TooManyColumns { line: usize, content: String },
 
     /// Parsing error at a line.
@@ -30,6 +32,143 @@ pub enum Error {
     )]
     WrongNumberOfData { n_data: usize },
 
+    /// Number of values does not match the shape of a matrix.
+    #[error("Incorrect number of data values: {n_data}; expected {expected} values for a {nrows} x {ncols} matrix.")]
+    IncorrectDataLength {
+        nrows: usize,
+        ncols: usize,
+        expected: usize,
+        n_data: usize,
+    },
+
+    /// The same entry has been given two different values.
+    #[error("Conflicting values for ({row}, {col}): {first_value} at line {first_line} and {second_value} at line {second_line}")]
+    ConflictingValues {
+        row: String,
+        col: String,
+        first_line: usize,
+        first_value: String,
+        second_line: usize,
+        second_value: String,
+    },
+
+    /// Values given more than once for the same entry can't be resolved with a duplicate policy.
+    #[error("Values of type {type_name} given more than once for ({row}, {col}) can't be resolved with {policy:?} policy")]
+    UnsupportedPolicy {
+        row: String,
+        col: String,
+        policy: DuplicatePolicy,
+        type_name: &'static str,
+    },
+
+    /// Values given more than once for an entry overflow their type when aggregated by a duplicate policy.
+    #[error("Values of type {type_name} given more than once for ({row}, {col}) overflow with {policy:?} policy")]
+    ValueOverflow {
+        row: String,
+        col: String,
+        policy: DuplicatePolicy,
+        type_name: &'static str,
+    },
+
+    /// A label has been given two different explicit indices.
+    #[error("Label '{label}' at line {line} has index {second_index}, but index {first_index} was given before")]
+    ConflictingIndex {
+        label: String,
+        first_index: usize,
+        second_index: usize,
+        line: usize,
+    },
+
+    /// Two different labels have been given the same explicit index.
+    #[error("Index {index} of label '{second_label}' at line {line} has already been given to label '{first_label}'")]
+    IndexCollision {
+        index: usize,
+        first_label: String,
+        second_label: String,
+        line: usize,
+    },
+
+    /// Explicit indices do not cover a contiguous range starting from 0.
+    #[error("Explicit indices are not contiguous: index {missing} is missing, while the largest index is {max_index}")]
+    NonContiguousIndices { missing: usize, max_index: usize },
+
+    /// A label to be added is already present in the matrix.
+    #[error("Label '{label}' is already present in the matrix")]
+    DuplicateLabel { label: String },
+
+    /// A label is not present in the matrix.
+    #[error("Unknown label: '{label}'")]
+    UnknownLabel { label: String },
+
+    /// Row or column index out of the bounds of a matrix.
+    #[error("Index ({row}, {col}) is out of bounds for a matrix of {nrows} rows and {ncols} columns")]
+    IndexOutOfBounds {
+        row: usize,
+        col: usize,
+        nrows: usize,
+        ncols: usize,
+    },
+
+    /// A selection of rows and columns would result in a matrix with no rows or no columns.
+    #[error("The selection holds {nrows} rows and {ncols} columns, but a matrix needs at least one of each")]
+    EmptySelection { nrows: usize, ncols: usize },
+
+    /// A permutation of rows or columns does not list each of them.
+    #[error("A permutation of {expected} rows or columns was expected, but {actual} indices were given")]
+    InvalidPermutation { expected: usize, actual: usize },
+
+    /// Operation requires a square matrix.
+    #[error("This operation requires a square matrix, but the matrix has {nrows} rows and {ncols} columns")]
+    NotSquare { nrows: usize, ncols: usize },
+
+    /// A matrix expected to be symmetric has different values at (i, j) and (j, i).
+    #[error("The matrix is not symmetric: value for ({row}, {col}) is {value}, but {transposed} for ({col}, {row})")]
+    NotSymmetric {
+        row: String,
+        col: String,
+        value: String,
+        transposed: String,
+    },
+
+    /// Rows and columns of a matrix expected to be symmetric have different labels.
+    #[error("Row and column labels differ at index {index}: '{row_label}' and '{col_label}'")]
+    LabelMismatch {
+        index: usize,
+        row_label: String,
+        col_label: String,
+    },
+
+    /// A label is too long to be stored in the requested format.
+    #[error("Label '{label}' is longer than {max_length} characters")]
+    LabelTooLong { label: String, max_length: usize },
+
+    /// A binary matrix file is malformed.
+    #[error("Invalid binary matrix file: {reason}")]
+    InvalidBinaryFormat { reason: String },
+
+    /// A binary matrix file has been written in a version of the format this crate can't read.
+    #[error("Unsupported version {version} of the binary matrix format; supported version: {supported}")]
+    UnsupportedVersion { version: u16, supported: u16 },
+
+    /// A Matrix Market file is malformed or in a variant of the format this crate can't read.
+    #[error("Invalid Matrix Market input at line {line}: {reason}")]
+    InvalidMatrixMarket { line: usize, reason: String },
+
+    /// Values of a matrix can't be written in the requested format.
+    #[error("Values of type {type_name} can't be written in the {format} format")]
+    UnsupportedElementType { type_name: &'static str, format: &'static str },
+
+    /// A NumPy `.npy` file or `.npz` archive is malformed or holds an array this crate can't read.
+    #[error("Invalid NumPy file: {reason}")]
+    InvalidNpyFormat { reason: String },
+
+    /// Values of a binary matrix or NumPy file are of another type than requested.
+    #[error("Matrix file holds values of type {found}, but {expected} was requested")]
+    ElementTypeMismatch {
+        expected: &'static str,
+        found: &'static str,
+    },
+
     /// Generic I/O error.
     #[error("I/O error: {0}")]
     IoError(#[from] std::io::Error),
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use crate::Error;
+
+/// Hash-based mapping between string labels and their 0-based indices.
+///
+/// Used both by [`DataMatrixBuilder`](crate::DataMatrixBuilder) while parsing input files
+/// and by [`DataMatrix`](crate::DataMatrix) to provide constant-time label lookups.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Indexer {
+    label_to_index: HashMap<String, usize>,
+    /// Labels registered with explicit indices, used to detect index collisions.
+    explicit_labels: HashMap<usize, String>,
+}
+
+impl Indexer {
+    pub(crate) fn new() -> Self {
+        Self {
+            label_to_index: HashMap::new(),
+            explicit_labels: HashMap::new(),
+        }
+    }
+
+    /// Creates an indexer for the given labels, where each label is mapped to its position.
+    ///
+    /// If a label appears more than once, its first occurrence wins.
+    pub(crate) fn from_labels(labels: &[String]) -> Self {
+        let mut label_to_index = HashMap::with_capacity(labels.len());
+        for (idx, label) in labels.iter().enumerate() {
+            label_to_index.entry(label.clone()).or_insert(idx);
+        }
+        Self {
+            label_to_index,
+            explicit_labels: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn add(&mut self, label: &str) -> usize {
+        if let Some(&idx) = self.label_to_index.get(label) {
+            idx
+        } else {
+            let idx = self.label_to_index.len();
+            self.label_to_index.insert(label.to_string(), idx);
+            idx
+        }
+    }
+
+    /// Maps a label to the given index, e.g. when a row or a column is appended to a matrix.
+    pub(crate) fn insert(&mut self, label: &str, idx: usize) {
+        self.label_to_index.insert(label.to_string(), idx);
+    }
+
+    /// Registers a label with an index given explicitly in an input file at the given line.
+    ///
+    /// Fails if the label has already been given a different index, or the index has already been
+    /// given to a different label.
+    pub(crate) fn add_explicit(&mut self, label: &str, idx: usize, line: usize) -> Result<(), Error> {
+        if let Some(&known) = self.label_to_index.get(label) {
+            if known != idx {
+                return Err(Error::ConflictingIndex {
+                    label: label.to_string(),
+                    first_index: known,
+                    second_index: idx,
+                    line,
+                });
+            }
+            return Ok(());
+        }
+        if let Some(other) = self.explicit_labels.get(&idx) {
+            return Err(Error::IndexCollision {
+                index: idx,
+                first_label: other.clone(),
+                second_label: label.to_string(),
+                line,
+            });
+        }
+        self.label_to_index.insert(label.to_string(), idx);
+        self.explicit_labels.insert(idx, label.to_string());
+        Ok(())
+    }
+
+    /// Checks that explicitly given indices cover the `0..n` range without gaps.
+    pub(crate) fn check_contiguous(&self) -> Result<(), Error> {
+        let n = self.label_to_index.len();
+        if self.label_to_index.values().all(|&idx| idx < n) {
+            return Ok(());
+        }
+        // --- indices are unique, so one of 0..n must be missing
+        let missing = (0..n)
+            .find(|idx| !self.explicit_labels.contains_key(idx))
+            .unwrap_or(n);
+        let max_index = self.label_to_index.values().copied().max().unwrap_or(0);
+        Err(Error::NonContiguousIndices { missing, max_index })
+    }
+
+    /// Returns the index of a label, if it is known.
+    pub(crate) fn get(&self, label: &str) -> Option<usize> {
+        self.label_to_index.get(label).copied()
+    }
+
+    /// Returns the index of a label or [`Error::UnknownLabel`] if the label is not known.
+    pub(crate) fn get_or_err(&self, label: &str) -> Result<usize, Error> {
+        self.get(label).ok_or_else(|| Error::UnknownLabel {
+            label: label.to_string(),
+        })
+    }
+
+    pub(crate) fn max_index(&self) -> usize {
+        self.label_to_index.len()
+    }
+
+    pub(crate) fn to_vec(&self) -> Vec<String> {
+        let mut result = vec!["".to_string(); self.label_to_index.len()];
+        for (label, &idx) in &self.label_to_index {
+            result[idx] = label.clone();
+        }
+        result
+    }
+}
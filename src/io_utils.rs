@@ -0,0 +1,244 @@
+use flate2::{bufread, write};
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, ErrorKind, Write};
+use std::path::Path;
+
+use crate::Error;
+
+/// Guess a field separator from the filename extension.
+///
+/// Supported (case-insensitive):
+/// - `csv` → `,`
+/// - `tsv`, `tab` → `\t`
+/// - `psv` (pipe-separated) → `|`
+/// - `ssv` (semicolon-separated) → `;`
+///
+/// Also handles compressed files like `data.csv.gz` (peels one layer).
+///
+/// By default returns ` ` (a space character) if the separator cannot be determined.
+///
+/// # Examples
+/// ```rust,ignore
+/// use std::path::Path;
+///
+/// assert_eq!(guess_separator("data.csv"), ',');
+/// assert_eq!(guess_separator("data.TSV"), '\t');
+/// assert_eq!(guess_separator("table.tab"), '\t');
+/// assert_eq!(guess_separator("log.psv"), '|');
+/// assert_eq!(guess_separator("semi.ssv"), ';');
+/// assert_eq!(guess_separator("archive.csv.gz"), ','); // compressed
+/// ```
+pub(crate) fn guess_separator<P: AsRef<Path>>(path: P) -> char {
+    let path = path.as_ref();
+
+    // Get the likely data extension, handling a single compression suffix.
+    let ext = match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) => {
+            let ext = ext.to_ascii_lowercase();
+            match ext.as_str() {
+                // Peel one compression layer and check the previous extension
+                "gz" | "bz2" | "xz" | "zst" | "zip" => {
+                    // file_stem() of "...csv.gz" is "....csv"
+                    path.file_stem()
+                        .and_then(|s| Path::new(s).extension())
+                        .and_then(|e| e.to_str())
+                        .map(|e| e.to_ascii_lowercase())
+                        .unwrap_or_default()
+                }
+                other => other.to_string(),
+            }
+        }
+        None => String::new(),
+    };
+
+    match ext.as_str() {
+        "dat" => ' ',
+        "csv" => ',',
+        "tsv" | "tab" => '\t',
+        "psv" => '|',
+        "ssv" => ';',
+        _ => ' ',
+    }
+}
+
+/// Compression of an input stream or an output file.
+///
+/// gzip is always supported; other formats require the cargo feature of the same name
+/// (`bzip2`, `xz` or `zstd`). Otherwise reading or writing such data fails with
+/// an [`Unsupported`](std::io::ErrorKind::Unsupported) I/O error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum Compression {
+    /// Plain, uncompressed data
+    None,
+    /// gzip compression (`.gz` files)
+    Gzip,
+    /// bzip2 compression (`.bz2` files)
+    Bzip2,
+    /// xz compression (`.xz` files)
+    Xz,
+    /// Zstandard compression (`.zst` files)
+    Zstd,
+}
+
+impl Compression {
+    /// Infers the compression from the extension of a file name, e.g. `.gz`.
+    pub(crate) fn from_path<P: AsRef<Path>>(path: P) -> Compression {
+        let ext = path.as_ref().extension().and_then(OsStr::to_str).map(|e| e.to_ascii_lowercase());
+        match ext.as_deref() {
+            Some("gz") => Compression::Gzip,
+            Some("bz2") => Compression::Bzip2,
+            Some("xz") => Compression::Xz,
+            Some("zst") => Compression::Zstd,
+            _ => Compression::None,
+        }
+    }
+
+    /// Detects the compression from the magic bytes a stream starts with.
+    pub(crate) fn from_magic(head: &[u8]) -> Compression {
+        const BZIP2_BLOCK: [u8; 6] = [0x31, 0x41, 0x59, 0x26, 0x53, 0x59];
+        const BZIP2_END: [u8; 6] = [0x17, 0x72, 0x45, 0x38, 0x50, 0x90];
+        match head {
+            [0x1f, 0x8b, ..] => Compression::Gzip,
+            [0xfd, b'7', b'z', b'X', b'Z', 0x00, ..] => Compression::Xz,
+            [0x28, 0xb5, 0x2f, 0xfd, ..] => Compression::Zstd,
+            // --- "BZh" may start a text line as well, so the block header is also checked
+            [b'B', b'Z', b'h', b'1'..=b'9', rest @ ..]
+                if rest.starts_with(&BZIP2_BLOCK) || rest.starts_with(&BZIP2_END) =>
+            {
+                Compression::Bzip2
+            }
+            _ => Compression::None,
+        }
+    }
+
+    /// Name of the cargo feature required to support this compression, if any.
+    fn feature(&self) -> Option<&'static str> {
+        match self {
+            Compression::None | Compression::Gzip => None,
+            Compression::Bzip2 => (!cfg!(feature = "bzip2")).then_some("bzip2"),
+            Compression::Xz => (!cfg!(feature = "xz")).then_some("xz"),
+            Compression::Zstd => (!cfg!(feature = "zstd")).then_some("zstd"),
+        }
+    }
+
+    /// Fails if support for this compression has not been compiled in.
+    fn check_supported(&self) -> io::Result<()> {
+        match self.feature() {
+            None => Ok(()),
+            Some(feature) => Err(io::Error::new(
+                ErrorKind::Unsupported,
+                format!("{self:?} compression requires the \"{feature}\" feature of data-matrix"),
+            )),
+        }
+    }
+}
+
+/// This function can open a regular file or a compressed one. A boxed reader to the content is returned.
+///
+/// When `compression` is not given, it is detected from the first bytes of the file, so a compressed
+/// file is recognised regardless of its extension.
+///
+/// The code has been copied from bioshell-io::utils
+pub(crate) fn open_file<P: AsRef<Path>>(
+    file_path: P,
+    compression: Option<Compression>,
+) -> io::Result<Box<dyn BufRead>> {
+    let path = file_path.as_ref();
+
+    if path.as_os_str().is_empty() {
+        return Err(io::Error::new(
+            ErrorKind::InvalidInput,
+            "Couldn't open file: empty path",
+        ));
+    }
+    let file = File::open(path)?;
+
+    decompress(BufReader::with_capacity(128 * 1024, file), compression)
+}
+
+/// Wraps a reader with a decoder of the given compression.
+///
+/// When `compression` is not given, it is detected from the first bytes of the stream.
+pub(crate) fn decompress<'a, R: BufRead + 'a>(
+    mut reader: R,
+    compression: Option<Compression>,
+) -> io::Result<Box<dyn BufRead + 'a>> {
+    let compression = match compression {
+        Some(c) => c,
+        None => Compression::from_magic(reader.fill_buf()?),
+    };
+    compression.check_supported()?;
+    Ok(match compression {
+        Compression::None => Box::new(reader),
+        Compression::Gzip => Box::new(BufReader::with_capacity(
+            128 * 1024,
+            bufread::MultiGzDecoder::new(reader),
+        )),
+        #[cfg(feature = "bzip2")]
+        Compression::Bzip2 => Box::new(BufReader::with_capacity(
+            128 * 1024,
+            bzip2::bufread::MultiBzDecoder::new(reader),
+        )),
+        #[cfg(feature = "xz")]
+        Compression::Xz => Box::new(BufReader::with_capacity(
+            128 * 1024,
+            xz2::bufread::XzDecoder::new_multi_decoder(reader),
+        )),
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => Box::new(BufReader::with_capacity(
+            128 * 1024,
+            zstd::stream::read::Decoder::with_buffer(reader)?,
+        )),
+        #[allow(unreachable_patterns)]
+        _ => unreachable!("unsupported compression has been rejected"),
+    })
+}
+
+/// Creates a file and passes a writer to it to the `write` closure.
+///
+/// The output is compressed according to the extension of the file name: `.gz`, `.bz2`, `.xz` or `.zst`.
+/// The stream is flushed (and the compressed stream finalized) after `write` returns successfully.
+pub(crate) fn write_file<P, F>(file_path: P, write: F) -> Result<(), Error>
+where
+    P: AsRef<Path>,
+    F: FnOnce(&mut dyn Write) -> Result<(), Error>,
+{
+    let path = file_path.as_ref();
+    let compression = Compression::from_path(path);
+    compression.check_supported()?;
+    let file = BufWriter::with_capacity(128 * 1024, File::create(path)?);
+
+    match compression {
+        Compression::Gzip => {
+            let mut encoder = write::GzEncoder::new(file, flate2::Compression::default());
+            write(&mut encoder)?;
+            encoder.finish()?.flush()?;
+        }
+        #[cfg(feature = "bzip2")]
+        Compression::Bzip2 => {
+            let mut encoder = bzip2::write::BzEncoder::new(file, bzip2::Compression::default());
+            write(&mut encoder)?;
+            encoder.finish()?.flush()?;
+        }
+        #[cfg(feature = "xz")]
+        Compression::Xz => {
+            let mut encoder = xz2::write::XzEncoder::new(file, 6);
+            write(&mut encoder)?;
+            encoder.finish()?.flush()?;
+        }
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => {
+            let mut encoder = zstd::stream::write::Encoder::new(file, 0)?;
+            write(&mut encoder)?;
+            encoder.finish()?.flush()?;
+        }
+        _ => {
+            let mut file = file;
+            write(&mut file)?;
+            file.flush()?;
+        }
+    }
+    Ok(())
+}
@@ -4,33 +4,87 @@
 //! # Two dimensional array indexed by string labels
 #![doc = include_str!("../README.rustdoc.md")]
 
+mod accumulator;
+pub mod binary;
 mod datamatrix_builder;
+mod datamatrix_view;
+mod datamatrix_writer;
+mod element;
 mod errors;
+mod indexer;
+mod io_utils;
+mod matrix_market;
+mod npy;
+mod packed_datamatrix;
+mod phylip;
+mod reordering;
+#[cfg(feature = "serde")]
+mod serialization;
+mod sparse_datamatrix;
 
+pub use crate::binary::BinaryElement;
+#[cfg(feature = "mmap")]
+pub use crate::binary::MappedDataMatrix;
+pub use crate::element::Element;
 pub use crate::errors::Error;
 use crate::Error::IncorrectMatrixLabels;
-pub use datamatrix_builder::DataMatrixBuilder;
+pub use datamatrix_builder::{DataMatrixBuilder, DuplicatePolicy};
+pub use datamatrix_view::DataMatrixView;
+pub use datamatrix_writer::{DataMatrixWriter, Layout};
+pub use io_utils::Compression;
+pub use packed_datamatrix::PackedDataMatrix;
+pub use reordering::Alignment;
+pub use sparse_datamatrix::SparseDataMatrix;
+use indexer::Indexer;
+use std::ops::{Bound, Index, IndexMut, Range, RangeBounds};
 
-/// A dense matrix of numeric values with labeled rows and columns.
+/// A dense matrix of values with labeled rows and columns.
+///
+/// Values are `f64` unless another element type is given, e.g. `DataMatrix<f32>` or `DataMatrix<u32>`;
+/// see [`Element`] for the types that can be loaded from files.
+///
+/// Values are stored in a single contiguous buffer in row-major order: the entry (i, j) is found
+/// at position `i * ncols + j` of [`as_slice()`](DataMatrix::as_slice), so the buffer can be handed
+/// over to other libraries (e.g. BLAS or numpy) without copying.
+///
+/// With the `serde` feature, a matrix is serialized as a struct of `row_labels`, `col_labels` and `values`
+/// packed row-wise. A symmetric matrix, which rows and columns share labels, takes a compact form instead:
+/// `labels` and `upper_triangle`, the latter packed row-wise with the diagonal. Both forms list positions
+/// of missing entries, if any, in the `missing` field. The shape is validated on deserialization.
 #[derive(Debug, Clone)]
-pub struct DataMatrix {
-    /// Matrix data: values indexed by (row, column).
-    data: Vec<Vec<f64>>,
+pub struct DataMatrix<T = f64> {
+    /// Matrix data: values packed row-wise.
+    data: Vec<T>,
+
+    /// Number of rows.
+    nrows: usize,
+
+    /// Number of columns, i.e. the stride between consecutive rows.
+    ncols: usize,
 
     /// Row labels (index -> label).
     row_labels: Vec<String>,
 
     /// Column labels (index -> label).
     col_labels: Vec<String>,
+
+    /// Row label -> row index lookup.
+    row_index: Indexer,
+
+    /// Column label -> column index lookup.
+    col_index: Indexer,
+
+    /// Flags of missing entries, packed row-wise; `None` when no entry is missing.
+    missing: Option<Vec<bool>>,
 }
 
-impl DataMatrix {
+impl<T> DataMatrix<T> {
     /// Creates a new DataMatrix from data and labels.
     ///
     /// Results in an error if the data shape does not match the labels. In daily work you might prefer
     /// to use [`DataMatrixBuilder`] to create a [`DataMatrix`] from a file or data.
     pub fn new(
-        data: Vec<Vec<f64>>,
+        data: Vec<Vec<T>>,
         row_labels: Vec<String>,
         col_labels: Vec<String>,
     ) -> Result<Self, Error> {
@@ -40,54 +94,471 @@ impl DataMatrix {
                 actual: data.len(),
             });
         }
-        if data.is_empty() || data[0].len() != col_labels.len() {
+        let ncols = data.first().map_or(0, Vec::len);
+        if data.is_empty() || ncols != col_labels.len() {
             return Err(IncorrectMatrixLabels {
                 expected: col_labels.len(),
-                actual: data[0].len(),
+                actual: ncols,
+            });
+        }
+        if let Some(row) = data.iter().find(|row| row.len() != ncols) {
+            return Err(IncorrectMatrixLabels {
+                expected: col_labels.len(),
+                actual: row.len(),
             });
         }
 
+        let mut values = Vec::with_capacity(data.len() * ncols);
+        for row in data {
+            values.extend(row);
+        }
+        Self::from_vec(values, row_labels, col_labels)
+    }
+
+    /// Creates a new DataMatrix from values packed row-wise and labels.
+    ///
+    /// The shape of the matrix is given by the number of row and column labels; an error is returned
+    /// if the number of values does not match it.
+    ///
+    /// # Example
+    /// ```rust
+    /// use data_matrix::{DataMatrix, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let labels = vec!["A".to_string(), "B".to_string()];
+    /// let matrix = DataMatrix::from_vec(vec![0.0, 1.5, 1.5, 0.0], labels.clone(), labels)?;
+    /// assert_eq!(matrix.row(1), &[1.5, 0.0]);
+    /// assert_eq!(matrix.into_vec(), vec![0.0, 1.5, 1.5, 0.0]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_vec(data: Vec<T>, row_labels: Vec<String>, col_labels: Vec<String>) -> Result<Self, Error> {
+        let (nrows, ncols) = (row_labels.len(), col_labels.len());
+        if nrows == 0 {
+            return Err(IncorrectMatrixLabels {
+                expected: ncols,
+                actual: 0,
+            });
+        }
+        if data.len() != nrows * ncols {
+            return Err(Error::IncorrectDataLength {
+                nrows,
+                ncols,
+                expected: nrows * ncols,
+                n_data: data.len(),
+            });
+        }
+
+        let row_index = Indexer::from_labels(&row_labels);
+        let col_index = Indexer::from_labels(&col_labels);
         Ok(Self {
             data,
+            nrows,
+            ncols,
             row_labels,
             col_labels,
+            row_index,
+            col_index,
+            missing: None,
         })
     }
 
+    /// Attaches flags of missing entries, packed row-wise, to this matrix.
+    ///
+    /// The flags are dropped when no entry is marked as missing.
+    pub(crate) fn with_missing(mut self, missing: Vec<bool>) -> Self {
+        debug_assert_eq!(missing.len(), self.nrows() * self.ncols());
+        self.missing = if missing.contains(&true) {
+            Some(missing)
+        } else {
+            None
+        };
+        self
+    }
+
     /// Returns the number of rows.
     pub fn nrows(&self) -> usize {
-        self.data.len()
+        self.nrows
     }
 
     /// Returns the number of columns.
     pub fn ncols(&self) -> usize {
-        if let Some(first_row) = self.data.first() {
-            first_row.len()
-        } else {
-            0
-        }
+        self.ncols
+    }
+
+    /// Returns the number of rows and columns.
+    pub fn shape(&self) -> (usize, usize) {
+        (self.nrows, self.ncols)
     }
 
     /// Gets the matrix entry at (i, j).
-    pub fn get(&self, i: usize, j: usize) -> Option<f64> {
-        self.data.get(i).and_then(|row| row.get(j)).copied()
+    pub fn get(&self, i: usize, j: usize) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.get_ref(i, j).cloned()
+    }
+
+    /// Gets a reference to the matrix entry at (i, j), which avoids copying e.g. a `String` value.
+    pub fn get_ref(&self, i: usize, j: usize) -> Option<&T> {
+        if i < self.nrows && j < self.ncols {
+            self.data.get(i * self.ncols + j)
+        } else {
+            None
+        }
     }
 
     /// Gets the matrix entry by row and column label.
-    pub fn get_by_label(&self, row_label: &str, col_label: &str) -> Option<f64> {
-        let row_idx = self.row_labels.iter().position(|r| r == row_label)?;
-        let col_idx = self.col_labels.iter().position(|c| c == col_label)?;
+    ///
+    /// Labels are resolved with a hash lookup, so the cost does not depend on the matrix size.
+    pub fn get_by_label(&self, row_label: &str, col_label: &str) -> Option<T>
+    where
+        T: Clone,
+    {
+        let row_idx = self.row_index.get(row_label)?;
+        let col_idx = self.col_index.get(col_label)?;
         self.get(row_idx, col_idx)
     }
 
-    /// Returns the label of a row by its index.
+    /// Gets a mutable reference to the matrix entry at (i, j).
+    ///
+    /// Returns [`Error::IndexOutOfBounds`] when (i, j) is out of bounds. Unlike [`set()`](DataMatrix::set),
+    /// this method leaves the [missing](DataMatrix::is_missing) flag of the entry as it is.
+    pub fn get_mut(&mut self, i: usize, j: usize) -> Result<&mut T, Error> {
+        let pos = self.position(i, j)?;
+        Ok(&mut self.data[pos])
+    }
+
+    /// Sets the matrix entry at (i, j); the entry is no longer [missing](DataMatrix::is_missing).
+    ///
+    /// Returns [`Error::IndexOutOfBounds`] when (i, j) is out of bounds.
+    ///
+    /// # Example
+    /// ```rust
+    /// use data_matrix::{DataMatrixBuilder, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let mut matrix = DataMatrixBuilder::new().symmetric(true).from_str("A B 1.5\nB C 2.0\n")?;
+    /// assert!(matrix.is_missing(0, 2));
+    /// matrix.set(0, 2, 3.5)?;
+    /// assert_eq!(matrix.get_by_label("A", "C"), Some(3.5));
+    /// assert!(!matrix.is_missing(0, 2));
+    /// matrix.set_by_label("C", "B", 4.0)?;
+    /// assert_eq!(matrix.get(2, 1), Some(4.0));
+    /// // --- the symmetric setter updates (j, i) as well
+    /// matrix.set_symmetric(0, 1, 0.5)?;
+    /// assert_eq!(matrix[(1, 0)], 0.5);
+    /// assert!(matrix.set(3, 0, 1.0).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set(&mut self, i: usize, j: usize, value: T) -> Result<(), Error> {
+        let pos = self.position(i, j)?;
+        self.data[pos] = value;
+        if let Some(missing) = self.missing.as_mut() {
+            missing[pos] = false;
+        }
+        Ok(())
+    }
+
+    /// Sets the matrix entry by row and column label; the entry is no longer [missing](DataMatrix::is_missing).
+    ///
+    /// Returns [`Error::UnknownLabel`] when any of the labels is not present in the matrix.
+    pub fn set_by_label(&mut self, row_label: &str, col_label: &str, value: T) -> Result<(), Error> {
+        let (i, j) = self.label_position(row_label, col_label)?;
+        self.set(i, j, value)
+    }
+
+    /// Sets both the (i, j) and (j, i) entries of a square matrix, keeping it symmetric.
+    ///
+    /// Returns [`Error::NotSquare`] for a matrix which is not square and [`Error::IndexOutOfBounds`]
+    /// when (i, j) is out of bounds.
+    pub fn set_symmetric(&mut self, i: usize, j: usize, value: T) -> Result<(), Error>
+    where
+        T: Clone,
+    {
+        if !self.is_square() {
+            return Err(Error::NotSquare {
+                nrows: self.nrows,
+                ncols: self.ncols,
+            });
+        }
+        self.position(i, j)?;
+        self.set(j, i, value.clone())?;
+        self.set(i, j, value)
+    }
+
+    /// Position of the entry (i, j) in the buffer of values.
+    fn position(&self, i: usize, j: usize) -> Result<usize, Error> {
+        if i < self.nrows && j < self.ncols {
+            Ok(i * self.ncols + j)
+        } else {
+            Err(Error::IndexOutOfBounds {
+                row: i,
+                col: j,
+                nrows: self.nrows,
+                ncols: self.ncols,
+            })
+        }
+    }
+
+    /// Row and column indices of an entry given by labels.
+    fn label_position(&self, row_label: &str, col_label: &str) -> Result<(usize, usize), Error> {
+        Ok((self.row_index.get_or_err(row_label)?, self.col_index.get_or_err(col_label)?))
+    }
+
+    /// Appends a row with a new label; the new entries hold the `fill` value and are marked as
+    /// [missing](DataMatrix::is_missing).
+    ///
+    /// Returns [`Error::DuplicateLabel`] if the matrix already has a row of that label.
+    ///
+    /// # Example
+    /// ```rust
+    /// use data_matrix::{DataMatrixBuilder, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let mut matrix = DataMatrixBuilder::new().symmetric(true).from_str("A B 1.5\nA C 2.0\nB C 2.5\n")?;
+    /// // --- a new sample: a row and a column at once
+    /// matrix.add_taxon("D", f64::NAN)?;
+    /// matrix.set_symmetric(3, 0, 0.5)?;
+    /// assert_eq!(matrix.shape(), (4, 4));
+    /// assert_eq!(matrix.get_by_label("A", "D"), Some(0.5));
+    /// assert!(matrix.is_missing(3, 1));
+    ///
+    /// matrix.remove_taxon("B")?;
+    /// assert_eq!(matrix.row_labels(), ["A", "C", "D"]);
+    /// assert_eq!(matrix.get_by_label("C", "A"), Some(2.0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn add_row<S: Into<String>>(&mut self, label: S, fill: T) -> Result<(), Error>
+    where
+        T: Clone,
+    {
+        let label = label.into();
+        if self.row_index.get(&label).is_some() {
+            return Err(Error::DuplicateLabel { label });
+        }
+        let ncols = self.ncols;
+        self.missing_flags().extend(std::iter::repeat_n(true, ncols));
+        self.data.extend(std::iter::repeat_n(fill, ncols));
+        self.row_index.insert(&label, self.nrows);
+        self.row_labels.push(label);
+        self.nrows += 1;
+        Ok(())
+    }
+
+    /// Appends a column with a new label; the new entries hold the `fill` value and are marked as
+    /// [missing](DataMatrix::is_missing).
+    ///
+    /// Returns [`Error::DuplicateLabel`] if the matrix already has a column of that label.
+    pub fn add_col<S: Into<String>>(&mut self, label: S, fill: T) -> Result<(), Error>
+    where
+        T: Clone,
+    {
+        let label = label.into();
+        if self.col_index.get(&label).is_some() {
+            return Err(Error::DuplicateLabel { label });
+        }
+        let (nrows, ncols) = (self.nrows, self.ncols);
+        let missing = std::mem::take(self.missing_flags());
+        *self.missing_flags() = widen(missing, nrows, ncols, true);
+        self.data = widen(std::mem::take(&mut self.data), nrows, ncols, fill);
+        self.col_index.insert(&label, self.ncols);
+        self.col_labels.push(label);
+        self.ncols += 1;
+        Ok(())
+    }
+
+    /// Appends a row and a column of the same new label to a square matrix, e.g. a new taxon
+    /// of a distance matrix; the new entries hold the `fill` value and are marked as [missing](DataMatrix::is_missing).
+    ///
+    /// Returns [`Error::NotSquare`] for a matrix which is not square and [`Error::DuplicateLabel`]
+    /// if the matrix already has a row or a column of that label.
+    pub fn add_taxon<S: Into<String>>(&mut self, label: S, fill: T) -> Result<(), Error>
+    where
+        T: Clone,
+    {
+        let label = label.into();
+        if !self.is_square() {
+            return Err(Error::NotSquare {
+                nrows: self.nrows,
+                ncols: self.ncols,
+            });
+        }
+        if self.row_index.get(&label).is_some() || self.col_index.get(&label).is_some() {
+            return Err(Error::DuplicateLabel { label });
+        }
+        self.add_col(label.clone(), fill.clone())?;
+        self.add_row(label, fill)
+    }
+
+    /// Removes the row of the given label.
+    ///
+    /// Returns [`Error::UnknownLabel`] if the matrix has no row of that label, and [`Error::EmptySelection`]
+    /// if it's the last row: a matrix needs at least one row and one column.
+    pub fn remove_row(&mut self, label: &str) -> Result<(), Error> {
+        let i = self.row_index.get_or_err(label)?;
+        if self.nrows == 1 {
+            return Err(Error::EmptySelection {
+                nrows: 0,
+                ncols: self.ncols,
+            });
+        }
+        let range = i * self.ncols..(i + 1) * self.ncols;
+        if let Some(missing) = self.missing.as_mut() {
+            missing.drain(range.clone());
+        }
+        self.data.drain(range);
+        self.row_labels.remove(i);
+        self.row_index = Indexer::from_labels(&self.row_labels);
+        self.nrows -= 1;
+        Ok(())
+    }
+
+    /// Removes the column of the given label.
+    ///
+    /// Returns [`Error::UnknownLabel`] if the matrix has no column of that label, and [`Error::EmptySelection`]
+    /// if it's the last column.
+    pub fn remove_col(&mut self, label: &str) -> Result<(), Error> {
+        let j = self.col_index.get_or_err(label)?;
+        if self.ncols == 1 {
+            return Err(Error::EmptySelection {
+                nrows: self.nrows,
+                ncols: 0,
+            });
+        }
+        let ncols = self.ncols;
+        if let Some(missing) = self.missing.as_mut() {
+            remove_column(missing, ncols, j);
+        }
+        remove_column(&mut self.data, ncols, j);
+        self.col_labels.remove(j);
+        self.col_index = Indexer::from_labels(&self.col_labels);
+        self.ncols -= 1;
+        Ok(())
+    }
+
+    /// Removes both the row and the column of the given label, e.g. a taxon of a distance matrix.
+    ///
+    /// Returns [`Error::UnknownLabel`] unless the matrix has both a row and a column of that label,
+    /// and [`Error::EmptySelection`] if it's the last row or the last column.
+    pub fn remove_taxon(&mut self, label: &str) -> Result<(), Error> {
+        self.row_index.get_or_err(label)?;
+        self.col_index.get_or_err(label)?;
+        self.remove_col(label)?;
+        self.remove_row(label)
+    }
+
+    /// Creates a new matrix of the given rows and columns, in the order of the given labels.
+    ///
+    /// Returns [`Error::UnknownLabel`] for a label this matrix doesn't have, and [`Error::DuplicateLabel`]
+    /// if a label is given twice.
+    ///
+    /// # Example
+    /// ```rust
+    /// use data_matrix::{DataMatrixBuilder, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let matrix = DataMatrixBuilder::new().symmetric(true).from_str("A B 1.5\nA C 2.0\nB C 2.5\n")?;
+    /// let selected = matrix.select(&["C", "A"], &["B"])?;
+    /// assert_eq!(selected.row_labels(), ["C", "A"]);
+    /// assert_eq!(selected.as_slice(), &[2.5, 1.5]);
+    ///
+    /// let upper = matrix.slice(..2, 1..)?;
+    /// assert_eq!(upper.col_labels(), ["B", "C"]);
+    /// let without_a = matrix.filter_rows(|label| label != "A")?;
+    /// assert_eq!(without_a.shape(), (2, 3));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn select(&self, rows: &[&str], cols: &[&str]) -> Result<Self, Error>
+    where
+        T: Clone,
+    {
+        let rows: Vec<usize> = rows.iter().map(|label| self.row_index.get_or_err(label)).collect::<Result<_, _>>()?;
+        let cols: Vec<usize> = cols.iter().map(|label| self.col_index.get_or_err(label)).collect::<Result<_, _>>()?;
+        self.select_indices(&rows, &cols)
+    }
+
+    /// Creates a new square matrix of the rows and columns of the given labels, e.g. a distance matrix
+    /// restricted to a subset of its taxa.
+    ///
+    /// Returns [`Error::UnknownLabel`] unless this matrix has both a row and a column of each label.
+    pub fn select_symmetric(&self, labels: &[&str]) -> Result<Self, Error>
+    where
+        T: Clone,
+    {
+        self.select(labels, labels)
+    }
+
+    /// Creates a new matrix of the rows and columns of the given indices, in the given order.
+    ///
+    /// Returns [`Error::IndexOutOfBounds`] for an index out of the shape of this matrix,
+    /// [`Error::DuplicateLabel`] if an index is given twice and [`Error::EmptySelection`]
+    /// if no row or no column is given.
+    pub fn select_indices(&self, rows: &[usize], cols: &[usize]) -> Result<Self, Error>
+    where
+        T: Clone,
+    {
+        Ok(self.view().select_indices(rows, cols)?.to_owned())
+    }
+
+    /// Creates a new matrix of the given ranges of rows and columns.
+    ///
+    /// Returns [`Error::IndexOutOfBounds`] if a range reaches beyond the shape of this matrix,
+    /// and [`Error::EmptySelection`] for an empty range.
+    pub fn slice<R, C>(&self, rows: R, cols: C) -> Result<Self, Error>
+    where
+        T: Clone,
+        R: RangeBounds<usize>,
+        C: RangeBounds<usize>,
+    {
+        let rows: Vec<usize> = index_range(rows, self.nrows).collect();
+        let cols: Vec<usize> = index_range(cols, self.ncols).collect();
+        self.select_indices(&rows, &cols)
+    }
+
+    /// Creates a new matrix of the rows which labels satisfy the predicate, and all the columns.
+    ///
+    /// Returns [`Error::EmptySelection`] if no row satisfies the predicate.
+    pub fn filter_rows<F: FnMut(&str) -> bool>(&self, mut predicate: F) -> Result<Self, Error>
+    where
+        T: Clone,
+    {
+        let rows: Vec<usize> = (0..self.nrows).filter(|&i| predicate(&self.row_labels[i])).collect();
+        let cols: Vec<usize> = (0..self.ncols).collect();
+        self.select_indices(&rows, &cols)
+    }
+
+    /// Creates a new matrix of all the rows, and the columns which labels satisfy the predicate.
+    ///
+    /// Returns [`Error::EmptySelection`] if no column satisfies the predicate.
+    pub fn filter_cols<F: FnMut(&str) -> bool>(&self, mut predicate: F) -> Result<Self, Error>
+    where
+        T: Clone,
+    {
+        let rows: Vec<usize> = (0..self.nrows).collect();
+        let cols: Vec<usize> = (0..self.ncols).filter(|&j| predicate(&self.col_labels[j])).collect();
+        self.select_indices(&rows, &cols)
+    }
+
+    /// Creates a view of all the rows and columns of this matrix, which borrows its values instead
+    /// of copying them; see [`DataMatrixView`].
+    pub fn view(&self) -> DataMatrixView<'_, T> {
+        DataMatrixView::new(self)
+    }
+
+    /// Flags of missing entries, allocated first if no entry has been missing so far.
+    fn missing_flags(&mut self) -> &mut Vec<bool> {
+        let n_values = self.data.len();
+        self.missing.get_or_insert_with(|| vec![false; n_values])
+    }
+
+    /// Returns the index of a row by its label.
     pub fn row_index(&self, label: &str) -> Option<usize> {
-        self.row_labels.iter().position(|r| r == label)
+        self.row_index.get(label)
     }
 
-    /// Returns the label of a column by its index.
+    /// Returns the index of a column by its label.
     pub fn col_index(&self, label: &str) -> Option<usize> {
-        self.col_labels.iter().position(|r| r == label)
+        self.col_index.get(label)
     }
 
     /// Returns the label of a row by its index.
@@ -114,13 +585,199 @@ impl DataMatrix {
         &self.col_labels
     }
 
-    /// Access the raw matrix data.
-    pub fn data(&self) -> &Vec<Vec<f64>> {
+    /// Returns a copy of the matrix data as a vector of rows.
+    ///
+    /// [`as_slice()`](DataMatrix::as_slice), [`row()`](DataMatrix::row) and [`rows()`](DataMatrix::rows)
+    /// give access to the values without copying them.
+    pub fn to_rows(&self) -> Vec<Vec<T>>
+    where
+        T: Clone,
+    {
+        self.rows().map(<[T]>::to_vec).collect()
+    }
+
+    /// Returns a copy of the matrix data as a vector of rows.
+    #[deprecated(since = "0.2.0", note = "copies all the values; use `as_slice()` or `rows()`, or `to_rows()` for a copy")]
+    pub fn data(&self) -> Vec<Vec<T>>
+    where
+        T: Clone,
+    {
+        self.to_rows()
+    }
+
+    /// Returns all the values of the matrix, packed row-wise.
+    pub fn as_slice(&self) -> &[T] {
         &self.data
     }
 
+    /// Returns all the values of the matrix, packed row-wise, for modification.
+    ///
+    /// Note that modified values are not marked as [present](DataMatrix::is_missing).
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+
+    /// Returns the values of the i-th row.
+    ///
+    /// # Panics
+    /// Panics if `i` is out of bounds.
+    pub fn row(&self, i: usize) -> &[T] {
+        assert!(i < self.nrows, "row index {i} out of bounds for a matrix of {} rows", self.nrows);
+        &self.data[i * self.ncols..(i + 1) * self.ncols]
+    }
+
+    /// Returns the values of the i-th row for modification.
+    ///
+    /// # Panics
+    /// Panics if `i` is out of bounds.
+    pub fn row_mut(&mut self, i: usize) -> &mut [T] {
+        assert!(i < self.nrows, "row index {i} out of bounds for a matrix of {} rows", self.nrows);
+        &mut self.data[i * self.ncols..(i + 1) * self.ncols]
+    }
+
+    /// Iterates over the rows of the matrix.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // --- max(1) avoids a panic of chunks() for a matrix with no columns
+        self.data.chunks(self.ncols.max(1)).take(self.nrows)
+    }
+
+    /// Consumes the matrix and returns its values, packed row-wise.
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+
     /// Checks if the matrix is square.
     pub fn is_square(&self) -> bool {
         self.nrows() == self.ncols()
     }
+
+    /// Checks whether the entry at (i, j) is missing.
+    ///
+    /// An entry is missing when the input file did not provide it or provided a NA token instead of a value
+    /// (see [`DataMatrixBuilder::na_tokens()`]). A missing entry holds the fill value set by
+    /// [`DataMatrixBuilder::missing_value()`]. Returns `false` when (i, j) is out of bounds.
+    ///
+    /// # Example
+    /// ```rust
+    /// use data_matrix::{DataMatrixBuilder, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let matrix = DataMatrixBuilder::new()
+    ///     .missing_value(f64::NAN)
+    ///     .from_file("./tests/test_files/three_columns_short.txt")?;
+    /// let (i, j) = (matrix.row_index("Alice").unwrap(), matrix.col_index("John").unwrap());
+    /// assert!(matrix.is_missing(i, j));
+    /// assert!(matrix.get(i, j).unwrap().is_nan());
+    /// assert_eq!(matrix.count_missing(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn is_missing(&self, i: usize, j: usize) -> bool {
+        match &self.missing {
+            Some(missing) if i < self.nrows() && j < self.ncols() => missing[i * self.ncols() + j],
+            _ => false,
+        }
+    }
+
+    /// Counts the missing entries of this matrix.
+    pub fn count_missing(&self) -> usize {
+        self.missing
+            .as_ref()
+            .map_or(0, |missing| missing.iter().filter(|&&m| m).count())
+    }
+}
+
+/// Access to the entry at `(row, column)`.
+///
+/// # Panics
+/// Panics if the index is out of bounds; use [`DataMatrix::get_ref()`] to check it.
+impl<T> Index<(usize, usize)> for DataMatrix<T> {
+    type Output = T;
+
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        match self.position(i, j) {
+            Ok(pos) => &self.data[pos],
+            Err(e) => panic!("{e}"),
+        }
+    }
+}
+
+/// Modification of the entry at `(row, column)`; the [missing](DataMatrix::is_missing) flag of the entry
+/// is left as it is.
+///
+/// # Panics
+/// Panics if the index is out of bounds; use [`DataMatrix::set()`] or [`DataMatrix::get_mut()`] to check it.
+impl<T> IndexMut<(usize, usize)> for DataMatrix<T> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut T {
+        match self.position(i, j) {
+            Ok(pos) => &mut self.data[pos],
+            Err(e) => panic!("{e}"),
+        }
+    }
+}
+
+/// Access to the entry at `(row label, column label)`.
+///
+/// # Panics
+/// Panics if any of the labels is unknown; use [`DataMatrix::get_by_label()`] to check them.
+impl<T> Index<(&str, &str)> for DataMatrix<T> {
+    type Output = T;
+
+    fn index(&self, (row_label, col_label): (&str, &str)) -> &T {
+        match self.label_position(row_label, col_label) {
+            Ok(position) => &self[position],
+            Err(e) => panic!("{e}"),
+        }
+    }
+}
+
+/// Modification of the entry at `(row label, column label)`; the [missing](DataMatrix::is_missing) flag
+/// of the entry is left as it is.
+///
+/// # Panics
+/// Panics if any of the labels is unknown; use [`DataMatrix::set_by_label()`] to check them.
+impl<T> IndexMut<(&str, &str)> for DataMatrix<T> {
+    fn index_mut(&mut self, (row_label, col_label): (&str, &str)) -> &mut T {
+        match self.label_position(row_label, col_label) {
+            Ok(position) => &mut self[position],
+            Err(e) => panic!("{e}"),
+        }
+    }
+}
+
+/// Appends a column of `fill` values to `nrows` rows of `ncols` values, packed row-wise.
+fn widen<T: Clone>(values: Vec<T>, nrows: usize, ncols: usize, fill: T) -> Vec<T> {
+    let mut widened = Vec::with_capacity(nrows * (ncols + 1));
+    let mut values = values.into_iter();
+    for _ in 0..nrows {
+        widened.extend(values.by_ref().take(ncols));
+        widened.push(fill.clone());
+    }
+    widened
+}
+
+/// Removes the j-th column of rows of `ncols` values, packed row-wise.
+fn remove_column<T>(values: &mut Vec<T>, ncols: usize, j: usize) {
+    let mut k = 0;
+    values.retain(|_| {
+        k += 1;
+        (k - 1) % ncols != j
+    });
+}
+
+/// Indices of the given range of `n` rows or columns.
+///
+/// A range reaching beyond `n` is cut after its first index out of bounds, which is enough
+/// for [`DataMatrix::select_indices()`] to report it.
+fn index_range<R: RangeBounds<usize>>(range: R, n: usize) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(&k) => k,
+        Bound::Excluded(&k) => k.saturating_add(1),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&k) => k.saturating_add(1),
+        Bound::Excluded(&k) => k,
+        Bound::Unbounded => n,
+    };
+    start..end.min(start.max(n).saturating_add(1))
 }
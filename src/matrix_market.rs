@@ -0,0 +1,260 @@
+//! Reading and writing matrices in the Matrix Market coordinate format.
+//!
+//! A Matrix Market file starts with a header line which gives the format and its qualifiers,
+//! followed by `%` comment lines, the size line (the numbers of rows, columns and entries)
+//! and one entry per line, with 1-based row and column indices:
+//! ```text
+//! %%MatrixMarket matrix coordinate real symmetric
+//! % row_labels: Alice Bob John
+//! 3 3 3
+//! 2 1 1.5
+//! 3 1 2.0
+//! 3 2 2.2
+//! ```
+//! A `symmetric` file lists entries of the lower triangle only; a `general` one lists all of them.
+//! The format has no place for labels: this crate stores them in `% row_labels:` and `% col_labels:`
+//! comments, as whitespace-separated lists which may continue over several such lines.
+//! The column labels of a symmetric file default to its row labels.
+use std::io::{BufRead, Write};
+
+use crate::accumulator::Accumulator;
+use crate::{DataMatrix, DuplicatePolicy, Element, Error};
+
+/// Header line of a Matrix Market file
+const BANNER: &str = "%%MatrixMarket";
+
+/// Entries of a Matrix Market file, with the shape and labels given in its header.
+pub(crate) struct MatrixMarketEntries<T> {
+    pub(crate) entries: Accumulator<T>,
+    pub(crate) nrows: usize,
+    pub(crate) ncols: usize,
+    /// `true` when only entries (i, j) where `i <= j` have been collected, to be mirrored to (j, i)
+    pub(crate) symmetric: bool,
+    pub(crate) row_labels: Option<Vec<String>>,
+    pub(crate) col_labels: Option<Vec<String>>,
+}
+
+fn invalid(line: usize, reason: &str) -> Error {
+    Error::InvalidMatrixMarket {
+        line,
+        reason: reason.to_string(),
+    }
+}
+
+/// Reads entries of a Matrix Market file in the coordinate format.
+///
+/// Entries which are given more than once are resolved according to `policy`; the entries of
+/// a `general` file are mirrored as well when `symmetric` is set.
+pub(crate) fn read_matrix_market<T: Element, R: BufRead>(
+    reader: R,
+    fill: T,
+    policy: DuplicatePolicy,
+    symmetric: bool,
+    sparse: bool,
+) -> Result<MatrixMarketEntries<T>, Error> {
+    let mut lines = reader.lines().enumerate().map(|(line_no, line)| line.map(|l| (line_no + 1, l)));
+
+    let (_, banner) = lines.next().transpose()?.ok_or_else(|| invalid(0, "empty Matrix Market input"))?;
+    let qualifiers: Vec<String> = banner.split_whitespace().map(str::to_lowercase).collect();
+    match qualifiers.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        [banner, ..] if *banner != BANNER.to_lowercase() => return Err(invalid(1, "the header line must start with %%MatrixMarket")),
+        [_, "matrix", "coordinate", "real" | "integer", "general"] => {}
+        [_, "matrix", "coordinate", "real" | "integer", "symmetric"] => {}
+        _ => return Err(invalid(1, &format!("unsupported format: '{}'; only real and integer matrices in the general or symmetric coordinate format can be read", banner.trim()))),
+    }
+    let symmetric = symmetric || qualifiers[4] == "symmetric";
+
+    // --- comments and label lists precede the size line
+    let mut row_labels: Option<Vec<String>> = None;
+    let mut col_labels: Option<Vec<String>> = None;
+    let (size_line_no, size_line) = loop {
+        let (line_no, line) = lines.next().transpose()?.ok_or_else(|| invalid(0, "the size line is missing"))?;
+        let line = line.trim();
+        if let Some(comment) = line.strip_prefix('%') {
+            let comment = comment.trim_start();
+            if let Some(labels) = comment.strip_prefix("row_labels:") {
+                row_labels.get_or_insert_with(Vec::new).extend(labels.split_whitespace().map(str::to_string));
+            } else if let Some(labels) = comment.strip_prefix("col_labels:") {
+                col_labels.get_or_insert_with(Vec::new).extend(labels.split_whitespace().map(str::to_string));
+            }
+        } else if !line.is_empty() {
+            break (line_no, line.to_string());
+        }
+    };
+    let size: Vec<usize> = size_line
+        .split_whitespace()
+        .map(|token| token.parse().map_err(|_| invalid(size_line_no, &format!("invalid size line '{size_line}'"))))
+        .collect::<Result<_, _>>()?;
+    let &[nrows, ncols, n_entries] = size.as_slice() else {
+        return Err(invalid(size_line_no, &format!("the size line '{size_line}' must give the numbers of rows, columns and entries")));
+    };
+    check_size::<T>(nrows, ncols, sparse).map_err(|reason| invalid(size_line_no, &format!("{reason}: '{size_line}'")))?;
+    if symmetric && nrows != ncols {
+        return Err(Error::NotSquare { nrows, ncols });
+    }
+    if symmetric && col_labels.is_none() {
+        col_labels = row_labels.clone();
+    }
+
+    let mut entries = if sparse {
+        Accumulator::sparse(fill, policy)
+    } else {
+        Accumulator::new(fill, policy)
+    };
+    let mut n_read = 0;
+    for line in lines {
+        let (line_no, line) = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('%') {
+            continue;
+        }
+        if n_read == n_entries {
+            return Err(invalid(line_no, &format!("more than {n_entries} entries given")));
+        }
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let &[i, j, value] = tokens.as_slice() else {
+            return Err(invalid(line_no, &format!("an entry must give a row index, a column index and a value: '{line}'")));
+        };
+        let index = |token: &str, n: usize| match token.parse::<usize>() {
+            Ok(k) if (1..=n).contains(&k) => Ok(k - 1),
+            _ => Err(invalid(line_no, &format!("index {token} out of the range 1..={n}"))),
+        };
+        let (i, j) = (index(i, nrows)?, index(j, ncols)?);
+        let value: T = value.parse().map_err(|_| Error::ParseError {
+            line: line_no,
+            content: value.to_string(),
+        })?;
+        let (i, j) = if symmetric { (i.min(j), i.max(j)) } else { (i, j) };
+        entries.add(i, j, value, line_no, || (label(&row_labels, i, "row"), label(&col_labels, j, "col")))?;
+        n_read += 1;
+    }
+    if n_read < n_entries {
+        return Err(invalid(0, &format!("expected {n_entries} entries, found {n_read}")));
+    }
+
+    Ok(MatrixMarketEntries {
+        entries,
+        nrows,
+        ncols,
+        symmetric,
+        row_labels,
+        col_labels,
+    })
+}
+
+/// Checks that a matrix of the shape given by a size line can be held in memory, before anything is
+/// allocated for it: its labels, and all its values unless it's stored as `sparse`.
+fn check_size<T>(nrows: usize, ncols: usize, sparse: bool) -> Result<(), &'static str> {
+    if nrows == 0 || ncols == 0 {
+        return Err("a matrix needs at least one row and one column");
+    }
+    let n_values = nrows.checked_mul(ncols).ok_or("the matrix is too large")?;
+    // --- memory is only reserved, then released at once; the size line may be made up
+    if !can_reserve::<String>(nrows.max(ncols)) || (!sparse && !can_reserve::<T>(n_values)) {
+        return Err("the matrix is too large");
+    }
+    Ok(())
+}
+
+/// Checks whether memory for `n` values of type `V` can be reserved.
+fn can_reserve<V>(n: usize) -> bool {
+    Vec::<V>::new().try_reserve_exact(n).is_ok()
+}
+
+/// Label of the k-th row or column to report a conflict, generated when no labels are given.
+fn label(labels: &Option<Vec<String>>, k: usize, prefix: &str) -> String {
+    match labels.as_ref().and_then(|labels| labels.get(k)) {
+        Some(label) => label.clone(),
+        None => format!("{prefix}-{}", k + 1),
+    }
+}
+
+/// Writes a matrix in the Matrix Market coordinate format; missing entries are skipped.
+///
+/// Values are declared as `integer` or `real` according to their type; other types are rejected.
+/// A `symmetric` matrix must be square and symmetric, missing entries included; only its lower triangle is written.
+pub(crate) fn write_matrix_market<T: Element>(
+    matrix: &DataMatrix<T>,
+    writer: &mut dyn Write,
+    symmetric: bool,
+    comments: &[String],
+) -> Result<(), Error> {
+    let field = T::MATRIX_MARKET_FIELD.ok_or(Error::UnsupportedElementType {
+        type_name: std::any::type_name::<T>(),
+        format: "Matrix Market",
+    })?;
+    if symmetric {
+        check_symmetric(matrix)?;
+    }
+    let entries = || {
+        (0..matrix.nrows())
+            .flat_map(move |i| (0..if symmetric { i + 1 } else { matrix.ncols() }).map(move |j| (i, j)))
+            .filter(|&(i, j)| !matrix.is_missing(i, j))
+    };
+
+    let qualifier = if symmetric { "symmetric" } else { "general" };
+    writeln!(writer, "{BANNER} matrix coordinate {field} {qualifier}")?;
+    for line in comments {
+        writeln!(writer, "% {}", line)?;
+    }
+    writeln!(writer, "% row_labels: {}", matrix.row_labels().join(" "))?;
+    if !symmetric {
+        writeln!(writer, "% col_labels: {}", matrix.col_labels().join(" "))?;
+    }
+    writeln!(writer, "{} {} {}", matrix.nrows(), matrix.ncols(), entries().count())?;
+    for (i, j) in entries() {
+        writeln!(writer, "{} {} {}", i + 1, j + 1, matrix.row(i)[j])?;
+    }
+    Ok(())
+}
+
+/// Checks that a matrix is square, that its rows and columns share their labels and that its entries (i, j)
+/// and (j, i) are equal, or missing both.
+fn check_symmetric<T: Element>(matrix: &DataMatrix<T>) -> Result<(), Error> {
+    if !matrix.is_square() {
+        return Err(Error::NotSquare {
+            nrows: matrix.nrows(),
+            ncols: matrix.ncols(),
+        });
+    }
+    if let Some(index) = (0..matrix.nrows()).find(|&i| matrix.row_label(i) != matrix.col_label(i)) {
+        return Err(Error::LabelMismatch {
+            index,
+            row_label: matrix.row_label(index).clone(),
+            col_label: matrix.col_label(index).clone(),
+        });
+    }
+    let shown = |i: usize, j: usize| {
+        if matrix.is_missing(i, j) {
+            "missing".to_string()
+        } else {
+            matrix.row(i)[j].to_string()
+        }
+    };
+    for i in 0..matrix.nrows() {
+        for j in 0..i {
+            let missing = matrix.is_missing(i, j);
+            if missing != matrix.is_missing(j, i) || (!missing && matrix.row(i)[j] != matrix.row(j)[i]) {
+                return Err(Error::NotSymmetric {
+                    row: matrix.row_label(i).clone(),
+                    col: matrix.row_label(j).clone(),
+                    value: shown(i, j),
+                    transposed: shown(j, i),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Reads labels from a companion file, one label per line; blank lines are skipped.
+pub(crate) fn read_label_file<R: BufRead>(reader: R) -> Result<Vec<String>, Error> {
+    let mut labels = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            labels.push(line.trim().to_string());
+        }
+    }
+    Ok(labels)
+}
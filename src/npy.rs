@@ -0,0 +1,356 @@
+//! NumPy `.npy` arrays and `.npz` archives.
+//!
+//! A `.npy` file holds the values of a matrix only, as a 2-D array in C (row-major) or Fortran
+//! (column-major) order. A `.npz` archive, available with the `npz` feature, bundles the values with
+//! labels: it holds `values.npy` and, optionally, `row_labels.npy` and `col_labels.npy` arrays of strings,
+//! as written by `numpy.savez(fname, values=..., row_labels=..., col_labels=...)`.
+use std::io::{self, BufRead, Read, Write};
+use std::path::Path;
+
+use crate::binary::{read_values, type_name, write_values, BinaryElement};
+use crate::io_utils::{open_file, write_file};
+use crate::{DataMatrix, DataMatrixBuilder, DataMatrixWriter, Error};
+
+#[cfg(feature = "npz")]
+mod npz;
+
+/// Magic bytes a `.npy` file starts with
+const MAGIC: &[u8; 6] = b"\x93NUMPY";
+
+/// Header of a `.npy` array.
+#[derive(Debug, Clone, PartialEq)]
+struct ArrayHeader {
+    /// data type, e.g. `<f8`
+    descr: String,
+    fortran_order: bool,
+    shape: Vec<usize>,
+}
+
+/// Error of a malformed `.npy` file
+fn invalid(reason: &str) -> Error {
+    Error::InvalidNpyFormat {
+        reason: reason.to_string(),
+    }
+}
+
+/// Maps an unexpected end of a file to a format error.
+fn truncated(e: io::Error) -> Error {
+    match e.kind() {
+        io::ErrorKind::UnexpectedEof => invalid("the file is truncated"),
+        _ => Error::IoError(e),
+    }
+}
+
+/// NumPy data type of values of type `T`, in the native byte order.
+fn descr<T: BinaryElement>() -> String {
+    let kind = match T::TYPE_CODE {
+        1..=4 => 'i',
+        5..=8 => 'u',
+        _ => 'f',
+    };
+    let order = match (T::SIZE, cfg!(target_endian = "little")) {
+        (1, _) => '|',
+        (_, true) => '<',
+        (_, false) => '>',
+    };
+    format!("{order}{kind}{}", T::SIZE)
+}
+
+/// Splits a NumPy data type such as `<f8` into its byte order (`true` for little-endian), kind and size.
+fn parse_descr(descr: &str) -> Result<(bool, char, usize), Error> {
+    let unsupported = || invalid(&format!("unsupported data type '{descr}'"));
+    let mut chars = descr.chars();
+    let little_endian = match chars.next() {
+        Some('<') => true,
+        Some('>') => false,
+        Some('|') | Some('=') => cfg!(target_endian = "little"),
+        _ => return Err(unsupported()),
+    };
+    let kind = chars.next().ok_or_else(unsupported)?;
+    let size = chars.as_str().parse().map_err(|_| unsupported())?;
+    Ok((little_endian, kind, size))
+}
+
+impl ArrayHeader {
+    fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let shape = match self.shape.as_slice() {
+            [n] => format!("({n},)"),
+            dims => format!("({})", dims.iter().map(|d| d.to_string()).collect::<Vec<_>>().join(", ")),
+        };
+        let fortran_order = if self.fortran_order { "True" } else { "False" };
+        let mut dict = format!("{{'descr': '{}', 'fortran_order': {fortran_order}, 'shape': {shape}, }}", self.descr);
+        // --- magic, version and length take 10 bytes; the dictionary ends with a newline,
+        // --- padded with spaces so values start at a multiple of 64 bytes
+        let total = (10 + dict.len() + 1).next_multiple_of(64);
+        dict.extend(std::iter::repeat_n(' ', total - 10 - dict.len() - 1));
+        dict.push('\n');
+        let len = u16::try_from(dict.len()).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "npy header too long"))?;
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[1, 0])?;
+        writer.write_all(&len.to_le_bytes())?;
+        writer.write_all(dict.as_bytes())
+    }
+
+    fn read<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        let mut prefix = [0u8; 8];
+        reader.read_exact(&mut prefix).map_err(truncated)?;
+        if &prefix[0..6] != MAGIC {
+            return Err(invalid("not a NumPy array file"));
+        }
+        let len = match prefix[6] {
+            1 => {
+                let mut len = [0u8; 2];
+                reader.read_exact(&mut len).map_err(truncated)?;
+                u16::from_le_bytes(len) as usize
+            }
+            2 | 3 => {
+                let mut len = [0u8; 4];
+                reader.read_exact(&mut len).map_err(truncated)?;
+                u32::from_le_bytes(len) as usize
+            }
+            major => return Err(invalid(&format!("unsupported format version {major}.{}", prefix[7]))),
+        };
+        let mut dict = Vec::new();
+        reader.take(len as u64).read_to_end(&mut dict)?;
+        if dict.len() < len {
+            return Err(invalid("the file is truncated"));
+        }
+        let dict = std::str::from_utf8(&dict).map_err(|_| invalid("the header is not valid text"))?;
+        Self::parse(dict)
+    }
+
+    /// Parses the header dictionary, e.g. `{'descr': '<f8', 'fortran_order': False, 'shape': (3, 4), }`.
+    fn parse(dict: &str) -> Result<Self, Error> {
+        let malformed = || invalid(&format!("malformed header: {}", dict.trim()));
+        let descr = field(dict, "descr").ok_or_else(malformed)?;
+        let quote = descr.chars().next().filter(|&c| c == '\'' || c == '"').ok_or_else(malformed)?;
+        let descr = descr[1..].split(quote).next().ok_or_else(malformed)?.to_string();
+        let fortran_order = match field(dict, "fortran_order") {
+            Some(value) if value.starts_with("True") => true,
+            Some(value) if value.starts_with("False") => false,
+            _ => return Err(malformed()),
+        };
+        let shape = field(dict, "shape").and_then(|s| s.strip_prefix('(')).ok_or_else(malformed)?;
+        let shape = shape.split(')').next().ok_or_else(malformed)?;
+        let shape = shape
+            .split(',')
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+            .map(|d| d.parse().map_err(|_| malformed()))
+            .collect::<Result<Vec<usize>, Error>>()?;
+
+        Ok(Self {
+            descr,
+            fortran_order,
+            shape,
+        })
+    }
+
+    /// Number of values of `size` bytes in the array; an array whose size in bytes overflows is rejected.
+    fn n_values(&self, size: usize) -> Result<usize, Error> {
+        let n_values = self.shape.iter().try_fold(1usize, |n, &d| n.checked_mul(d));
+        n_values.filter(|n| n.checked_mul(size).is_some()).ok_or_else(|| invalid("the array is too large"))
+    }
+}
+
+/// Returns the text following `'key':` in a header dictionary.
+fn field<'a>(dict: &'a str, key: &str) -> Option<&'a str> {
+    let start = dict.find(&format!("'{key}'")).or_else(|| dict.find(&format!("\"{key}\"")))?;
+    dict[start + key.len() + 2..].trim_start().strip_prefix(':').map(str::trim_start)
+}
+
+/// Writes values of a matrix as a 2-D `.npy` array.
+fn write_matrix<T: BinaryElement, W: Write>(matrix: &DataMatrix<T>, fortran_order: bool, writer: &mut W) -> Result<(), Error> {
+    let header = ArrayHeader {
+        descr: descr::<T>(),
+        fortran_order,
+        shape: vec![matrix.nrows(), matrix.ncols()],
+    };
+    header.write(writer)?;
+    if fortran_order {
+        let columns = (0..matrix.ncols()).flat_map(|j| matrix.rows().map(move |row| &row[j]));
+        write_values(writer, columns)?;
+    } else {
+        write_values(writer, matrix.as_slice().iter())?;
+    }
+    Ok(())
+}
+
+/// Reads a 2-D `.npy` array of values of type `T`; returns its shape and values in row-major order.
+fn read_matrix<T: BinaryElement, R: Read>(reader: &mut R) -> Result<(usize, usize, Vec<T>), Error> {
+    let header = ArrayHeader::read(reader)?;
+    let (little_endian, kind, size) = parse_descr(&header.descr)?;
+    let code = match (kind, size) {
+        ('i', 1) => 1,
+        ('i', 2) => 2,
+        ('i', 4) => 3,
+        ('i', 8) => 4,
+        ('u', 1) => 5,
+        ('u', 2) => 6,
+        ('u', 4) => 7,
+        ('u', 8) => 8,
+        ('f', 4) => 9,
+        ('f', 8) => 10,
+        _ => return Err(invalid(&format!("unsupported data type '{}'", header.descr))),
+    };
+    if code != T::TYPE_CODE {
+        return Err(Error::ElementTypeMismatch {
+            expected: type_name(T::TYPE_CODE),
+            found: type_name(code),
+        });
+    }
+    let &[nrows, ncols] = header.shape.as_slice() else {
+        return Err(invalid(&format!("expected a 2-D array, found shape {:?}", header.shape)));
+    };
+    let values = read_values(reader, header.n_values(T::SIZE)?, little_endian).map_err(truncated)?;
+    if !header.fortran_order {
+        return Ok((nrows, ncols, values));
+    }
+    let mut transposed = Vec::with_capacity(values.len());
+    for i in 0..nrows {
+        transposed.extend((0..ncols).map(|j| values[j * nrows + i]));
+    }
+    Ok((nrows, ncols, transposed))
+}
+
+/// Writes labels as a 1-D `.npy` array of unicode strings, the type NumPy gives to an array of `str`.
+#[cfg(feature = "npz")]
+fn write_labels<W: Write>(labels: &[String], writer: &mut W) -> Result<(), Error> {
+    let width = labels.iter().map(|l| l.chars().count()).max().unwrap_or(0).max(1);
+    let order = if cfg!(target_endian = "little") { '<' } else { '>' };
+    let header = ArrayHeader {
+        descr: format!("{order}U{width}"),
+        fortran_order: false,
+        shape: vec![labels.len()],
+    };
+    header.write(writer)?;
+    let mut buffer = Vec::with_capacity(width * 4);
+    for label in labels {
+        buffer.clear();
+        let chars = label.chars().map(|c| c as u32).chain(std::iter::repeat(0));
+        chars.take(width).for_each(|c| buffer.extend_from_slice(&c.to_ne_bytes()));
+        writer.write_all(&buffer)?;
+    }
+    Ok(())
+}
+
+/// Reads a 1-D `.npy` array of unicode (`U`) or byte (`S`) strings.
+#[cfg(feature = "npz")]
+fn read_labels<R: Read>(reader: &mut R) -> Result<Vec<String>, Error> {
+    let header = ArrayHeader::read(reader)?;
+    let (little_endian, kind, width) = parse_descr(&header.descr)?;
+    let &[n] = header.shape.as_slice() else {
+        return Err(invalid(&format!("expected a 1-D array of labels, found shape {:?}", header.shape)));
+    };
+    let item_size = match kind {
+        'U' => width.checked_mul(4),
+        'S' => Some(width),
+        _ => None,
+    };
+    let item_size = item_size.ok_or_else(|| invalid(&format!("labels must be strings, found data type '{}'", header.descr)))?;
+
+    let mut labels = Vec::with_capacity(n.min(1 << 20));
+    let mut item = vec![0u8; item_size];
+    for _ in 0..n {
+        reader.read_exact(&mut item).map_err(truncated)?;
+        let label = if kind == 'U' {
+            item.chunks_exact(4)
+                .map(|c| {
+                    let c = c.try_into().expect("4 bytes");
+                    let code = if little_endian { u32::from_le_bytes(c) } else { u32::from_be_bytes(c) };
+                    char::from_u32(code).ok_or_else(|| invalid("a label is not valid unicode"))
+                })
+                .collect::<Result<String, Error>>()?
+        } else {
+            String::from_utf8(item.clone()).map_err(|_| invalid("a label is not valid UTF-8"))?
+        };
+        labels.push(label.trim_end_matches('\0').to_string());
+    }
+    Ok(labels)
+}
+
+impl DataMatrixWriter {
+    /// Writes values of a matrix into a NumPy `.npy` file.
+    ///
+    /// Labels are not stored: write a `.npz` archive, available with the `npz` feature, to keep them.
+    /// Values are written in Fortran (column-major) order if `fortran_order` is `true`, in C order otherwise;
+    /// settings of this writer are ignored, as are [missing](DataMatrix::is_missing) flags.
+    ///
+    /// # Example
+    /// ```rust
+    /// use data_matrix::{DataMatrixBuilder, DataMatrixWriter, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let matrix = DataMatrixBuilder::new()
+    ///     .skip_header(true)
+    ///     .index_columns(3, 4)
+    ///     .symmetric(true)
+    ///     .from_file("./tests/test_files/cities_by_distance.csv.gz")?;
+    /// let fname = std::env::temp_dir().join(format!("cities_doc_{}.npy", std::process::id()));
+    /// DataMatrixWriter::new().to_npy_file(&matrix, &fname, false)?;
+    /// // --- in Python: numpy.load("cities_doc.npy")
+    /// let copy = DataMatrixBuilder::new().labels(matrix.row_labels().to_vec()).from_npy_file(&fname)?;
+    /// assert_eq!(copy.as_slice(), matrix.as_slice());
+    /// # std::fs::remove_file(&fname)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_npy_file<T: BinaryElement, P: AsRef<Path>>(&self, matrix: &DataMatrix<T>, path: P, fortran_order: bool) -> Result<(), Error> {
+        write_file(path, |mut writer| write_matrix(matrix, fortran_order, &mut writer))
+    }
+
+    /// Writes values of a matrix as a NumPy `.npy` array to any writer.
+    pub fn to_npy_writer<T: BinaryElement, W: Write>(&self, matrix: &DataMatrix<T>, mut writer: W, fortran_order: bool) -> Result<(), Error> {
+        write_matrix(matrix, fortran_order, &mut writer)
+    }
+}
+
+impl<T: BinaryElement> DataMatrixBuilder<T> {
+    /// Loads a matrix from a NumPy `.npy` file holding a 2-D array.
+    ///
+    /// Arrays in both C and Fortran order are accepted, in either byte order; values must be of the element
+    /// type of this builder, otherwise [`Error::ElementTypeMismatch`] is returned. Since the file holds
+    /// no labels, the labels set by [`labels()`](DataMatrixBuilder::labels) are used for both rows and columns;
+    /// otherwise labels are generated as for [`from_data()`](DataMatrixBuilder::from_data).
+    pub fn from_npy_file<P: AsRef<Path>>(self, filename: P) -> Result<DataMatrix<T>, Error> {
+        let reader = open_file(filename, None)?;
+        self.from_npy_reader(reader)
+    }
+
+    /// Loads a matrix from a NumPy `.npy` array read from any reader.
+    pub fn from_npy_reader<R: BufRead>(self, mut reader: R) -> Result<DataMatrix<T>, Error> {
+        let (nrows, ncols, values) = read_matrix(&mut reader)?;
+        let (row_labels, col_labels) = self.npy_labels(nrows, ncols, None, None)?;
+        DataMatrix::from_vec(values, row_labels, col_labels)
+    }
+
+    /// Row and column labels of a matrix read from NumPy arrays: labels stored in a file come first,
+    /// then labels of this builder, otherwise labels are generated.
+    fn npy_labels(
+        &self,
+        nrows: usize,
+        ncols: usize,
+        row_labels: Option<Vec<String>>,
+        col_labels: Option<Vec<String>>,
+    ) -> Result<(Vec<String>, Vec<String>), Error> {
+        let labels = |stored: Option<Vec<String>>, n: usize, prefix: &str| match (stored, &self.labels) {
+            (Some(stored), _) => stored,
+            (None, Some(given)) => given.clone(),
+            (None, None) => (0..n).map(|i| format!("{prefix}-{}", i + 1)).collect(),
+        };
+        let (row_labels, col_labels) = (labels(row_labels, nrows, "row"), labels(col_labels, ncols, "col"));
+        if row_labels.len() != nrows {
+            return Err(Error::IncorrectMatrixLabels {
+                expected: row_labels.len(),
+                actual: nrows,
+            });
+        }
+        if col_labels.len() != ncols {
+            return Err(Error::IncorrectMatrixLabels {
+                expected: col_labels.len(),
+                actual: ncols,
+            });
+        }
+        Ok((row_labels, col_labels))
+    }
+}
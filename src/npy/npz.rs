@@ -0,0 +1,110 @@
+//! NumPy `.npz` archives: a matrix stored as named `.npy` arrays in a zip file.
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, Write};
+use std::path::Path;
+
+use zip::result::ZipError;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use super::{invalid, read_labels, read_matrix, write_labels, write_matrix};
+use crate::binary::BinaryElement;
+use crate::{DataMatrix, DataMatrixBuilder, DataMatrixWriter, Error};
+
+/// Name of the array of values in an archive
+const VALUES: &str = "values.npy";
+/// Name of the array of row labels in an archive
+const ROW_LABELS: &str = "row_labels.npy";
+/// Name of the array of column labels in an archive
+const COL_LABELS: &str = "col_labels.npy";
+
+fn zip_error(e: ZipError) -> Error {
+    match e {
+        ZipError::Io(e) => Error::IoError(e),
+        e => invalid(&e.to_string()),
+    }
+}
+
+impl DataMatrixWriter {
+    /// Writes a matrix into a NumPy `.npz` archive.
+    ///
+    /// The archive holds three arrays: `values`, `row_labels` and `col_labels`, so in Python
+    /// `numpy.load(fname)["values"]` gives the values and the labels are arrays of `str`.
+    /// As for [`to_npy_file()`](DataMatrixWriter::to_npy_file), values are written in Fortran order if
+    /// `fortran_order` is `true` and [missing](DataMatrix::is_missing) flags are not stored. Arrays are
+    /// stored uncompressed, as by `numpy.savez()`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use data_matrix::{DataMatrixBuilder, DataMatrixWriter, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let matrix = DataMatrixBuilder::new()
+    ///     .skip_header(true)
+    ///     .index_columns(3, 4)
+    ///     .symmetric(true)
+    ///     .from_file("./tests/test_files/cities_by_distance.csv.gz")?;
+    /// let fname = std::env::temp_dir().join(format!("cities_doc_{}.npz", std::process::id()));
+    /// DataMatrixWriter::new().to_npz_file(&matrix, &fname, false)?;
+    /// let copy = DataMatrixBuilder::new().from_npz_file(&fname)?;
+    /// assert_eq!(copy.get_by_label("Tokyo", "Paris"), matrix.get_by_label("Tokyo", "Paris"));
+    /// # std::fs::remove_file(&fname)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_npz_file<T: BinaryElement, P: AsRef<Path>>(&self, matrix: &DataMatrix<T>, path: P, fortran_order: bool) -> Result<(), Error> {
+        let writer = BufWriter::new(File::create(path)?);
+        self.to_npz_writer(matrix, writer, fortran_order)
+    }
+
+    /// Writes a matrix as a NumPy `.npz` archive to any seekable writer.
+    pub fn to_npz_writer<T: BinaryElement, W: Write + Seek>(&self, matrix: &DataMatrix<T>, writer: W, fortran_order: bool) -> Result<(), Error> {
+        // --- entries larger than 4 GiB need the zip64 extension; 1024 bytes bound the size of an array header
+        let entry_size = matrix.nrows() * matrix.ncols() * T::SIZE + 1024;
+        let options = SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Stored)
+            .large_file(entry_size > u32::MAX as usize);
+
+        let mut archive = ZipWriter::new(writer);
+        archive.start_file(VALUES, options).map_err(zip_error)?;
+        write_matrix(matrix, fortran_order, &mut archive)?;
+        let options = options.large_file(false);
+        archive.start_file(ROW_LABELS, options).map_err(zip_error)?;
+        write_labels(matrix.row_labels(), &mut archive)?;
+        archive.start_file(COL_LABELS, options).map_err(zip_error)?;
+        write_labels(matrix.col_labels(), &mut archive)?;
+        archive.finish().map_err(zip_error)?.flush()?;
+        Ok(())
+    }
+}
+
+impl<T: BinaryElement> DataMatrixBuilder<T> {
+    /// Loads a matrix from a NumPy `.npz` archive.
+    ///
+    /// The archive must hold a 2-D `values` array, stored or deflated, e.g. written by `numpy.savez()` or
+    /// `numpy.savez_compressed()`. Labels are read from the `row_labels` and `col_labels` arrays of strings;
+    /// when any of them is absent, labels are taken from [`labels()`](DataMatrixBuilder::labels) or generated,
+    /// as for [`from_npy_file()`](DataMatrixBuilder::from_npy_file).
+    pub fn from_npz_file<P: AsRef<Path>>(self, path: P) -> Result<DataMatrix<T>, Error> {
+        let reader = BufReader::new(File::open(path)?);
+        self.from_npz_reader(reader)
+    }
+
+    /// Loads a matrix from a NumPy `.npz` archive read from any seekable reader.
+    pub fn from_npz_reader<R: Read + Seek>(self, reader: R) -> Result<DataMatrix<T>, Error> {
+        let mut archive = ZipArchive::new(reader).map_err(zip_error)?;
+        let (nrows, ncols, values) = match archive.by_name(VALUES) {
+            Ok(mut entry) => read_matrix(&mut entry)?,
+            Err(ZipError::FileNotFound) => return Err(invalid(&format!("the archive has no {VALUES} array"))),
+            Err(e) => return Err(zip_error(e)),
+        };
+        let mut labels = |name: &str| match archive.by_name(name) {
+            Ok(mut entry) => read_labels(&mut entry).map(Some),
+            Err(ZipError::FileNotFound) => Ok(None),
+            Err(e) => Err(zip_error(e)),
+        };
+        let (row_labels, col_labels) = (labels(ROW_LABELS)?, labels(COL_LABELS)?);
+
+        let (row_labels, col_labels) = self.npy_labels(nrows, ncols, row_labels, col_labels)?;
+        DataMatrix::from_vec(values, row_labels, col_labels)
+    }
+}
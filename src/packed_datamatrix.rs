@@ -0,0 +1,325 @@
+use std::fmt::Display;
+
+use crate::indexer::Indexer;
+use crate::{DataMatrix, Error};
+
+/// A symmetric matrix of values with labeled rows and columns, which stores only its upper triangle.
+///
+/// Values are packed row by row: the first row holds entries (0, 0), (0, 1), ..., (0, n-1),
+/// the second one (1, 1), ..., (1, n-1) and so on, which takes half the memory of a [`DataMatrix`].
+/// The diagonal may also be left out, e.g. for distance matrices: diagonal entries then read as
+/// `T::default()`, i.e. zero for numeric types.
+///
+/// Entries below the diagonal are mirrored: [`get(i, j)`](PackedDataMatrix::get) and
+/// [`get(j, i)`](PackedDataMatrix::get) return the same value, and [`iter()`](PackedDataMatrix::iter)
+/// visits all the entries of the full matrix. Rows and columns share the same labels.
+///
+/// A packed matrix is loaded with [`DataMatrixBuilder::from_file_packed()`](crate::DataMatrixBuilder::from_file_packed)
+/// or converted from a dense one with [`from_dense()`](PackedDataMatrix::from_dense).
+///
+/// # Example
+/// ```rust
+/// use data_matrix::{DataMatrixBuilder, Error};
+/// # fn main() -> Result<(), Error> {
+/// let matrix = DataMatrixBuilder::new()
+///     .store_diagonal(false)
+///     .from_reader_packed("A B 1.5\nA C 2.0\nC B 0.5\n".as_bytes())?;
+/// assert_eq!(matrix.as_slice(), &[1.5, 2.0, 0.5]);
+/// assert_eq!(matrix.get_by_label("B", "C"), Some(0.5));
+/// assert_eq!(matrix.get_by_label("C", "B"), Some(0.5));
+/// assert_eq!(matrix.get_by_label("B", "B"), Some(0.0));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PackedDataMatrix<T = f64> {
+    /// Upper triangle of the matrix, packed row-wise.
+    data: Vec<T>,
+
+    /// Number of rows (and columns).
+    n: usize,
+
+    /// The value of diagonal entries when they are not stored.
+    diagonal: Option<T>,
+
+    /// Labels of rows and columns (index -> label).
+    labels: Vec<String>,
+
+    /// Label -> index lookup.
+    index: Indexer,
+
+    /// Flags of missing entries, packed as the values; `None` when no entry is missing.
+    missing: Option<Vec<bool>>,
+}
+
+impl<T> PackedDataMatrix<T> {
+    /// Creates a new PackedDataMatrix from the upper triangle of a matrix, packed row-wise, and labels.
+    ///
+    /// For `n` labels, `n * (n + 1) / 2` values are expected if `with_diagonal` is `true`
+    /// and `n * (n - 1) / 2` otherwise.
+    ///
+    /// # Example
+    /// ```rust
+    /// use data_matrix::{PackedDataMatrix, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let labels = vec!["A".to_string(), "B".to_string(), "C".to_string()];
+    /// let matrix = PackedDataMatrix::from_vec(vec![1, 2, 3, 4, 5, 6], labels, true)?;
+    /// assert_eq!(matrix.get(2, 1), Some(5));
+    /// assert_eq!(matrix.row(1).copied().collect::<Vec<_>>(), vec![2, 4, 5]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_vec(data: Vec<T>, labels: Vec<String>, with_diagonal: bool) -> Result<Self, Error>
+    where
+        T: Default,
+    {
+        let n = labels.len();
+        if n == 0 {
+            return Err(Error::IncorrectMatrixLabels {
+                expected: 0,
+                actual: 0,
+            });
+        }
+        let expected = packed_len(n, with_diagonal);
+        if data.len() != expected {
+            return Err(Error::IncorrectDataLength {
+                nrows: n,
+                ncols: n,
+                expected,
+                n_data: data.len(),
+            });
+        }
+
+        let index = Indexer::from_labels(&labels);
+        Ok(Self {
+            data,
+            n,
+            diagonal: (!with_diagonal).then(T::default),
+            labels,
+            index,
+            missing: None,
+        })
+    }
+
+    /// Converts a dense matrix into a packed one.
+    ///
+    /// Fails with [`Error::NotSquare`], [`Error::LabelMismatch`] or [`Error::NotSymmetric`] unless the matrix
+    /// is symmetric, with the same labels of rows and columns. When `with_diagonal` is `false`, diagonal
+    /// values of the dense matrix are dropped.
+    pub fn from_dense(matrix: &DataMatrix<T>, with_diagonal: bool) -> Result<Self, Error>
+    where
+        T: Clone + Default + PartialEq + Display,
+    {
+        if !matrix.is_square() {
+            return Err(Error::NotSquare {
+                nrows: matrix.nrows(),
+                ncols: matrix.ncols(),
+            });
+        }
+        if let Some(index) = (0..matrix.nrows()).find(|&i| matrix.row_label(i) != matrix.col_label(i)) {
+            return Err(Error::LabelMismatch {
+                index,
+                row_label: matrix.row_label(index).clone(),
+                col_label: matrix.col_label(index).clone(),
+            });
+        }
+        let n = matrix.nrows();
+        let mut data = Vec::with_capacity(packed_len(n, with_diagonal));
+        let mut missing = Vec::with_capacity(packed_len(n, with_diagonal));
+        for i in 0..n {
+            let first = if with_diagonal { i } else { i + 1 };
+            for j in first..n {
+                let (value, transposed) = (&matrix.row(i)[j], &matrix.row(j)[i]);
+                if value != transposed {
+                    return Err(Error::NotSymmetric {
+                        row: matrix.row_label(i).clone(),
+                        col: matrix.row_label(j).clone(),
+                        value: value.to_string(),
+                        transposed: transposed.to_string(),
+                    });
+                }
+                data.push(value.clone());
+                missing.push(matrix.is_missing(i, j));
+            }
+        }
+
+        Ok(Self::from_vec(data, matrix.row_labels().to_vec(), with_diagonal)?.with_missing(missing))
+    }
+
+    /// Attaches flags of missing entries, packed as the values, to this matrix.
+    ///
+    /// The flags are dropped when no entry is marked as missing.
+    pub(crate) fn with_missing(mut self, missing: Vec<bool>) -> Self {
+        debug_assert_eq!(missing.len(), self.data.len());
+        self.missing = if missing.contains(&true) {
+            Some(missing)
+        } else {
+            None
+        };
+        self
+    }
+
+    /// Converts this matrix into a dense one, where both (i, j) and (j, i) entries are stored.
+    pub fn to_dense(&self) -> DataMatrix<T>
+    where
+        T: Clone,
+    {
+        let values = self.iter().map(|(_, _, value)| value.clone()).collect();
+        let missing = (0..self.n)
+            .flat_map(|i| (0..self.n).map(move |j| self.is_missing(i, j)))
+            .collect();
+        DataMatrix::from_vec(values, self.labels.clone(), self.labels.clone())
+            .expect("a packed matrix has at least one row")
+            .with_missing(missing)
+    }
+
+    /// Returns the number of rows.
+    pub fn nrows(&self) -> usize {
+        self.n
+    }
+
+    /// Returns the number of columns, which is the same as the number of rows.
+    pub fn ncols(&self) -> usize {
+        self.n
+    }
+
+    /// Returns the number of rows and columns.
+    pub fn shape(&self) -> (usize, usize) {
+        (self.n, self.n)
+    }
+
+    /// Checks whether diagonal entries are stored.
+    pub fn has_diagonal(&self) -> bool {
+        self.diagonal.is_none()
+    }
+
+    /// Gets the matrix entry at (i, j); (j, i) gives the same value.
+    pub fn get(&self, i: usize, j: usize) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.get_ref(i, j).cloned()
+    }
+
+    /// Gets a reference to the matrix entry at (i, j).
+    pub fn get_ref(&self, i: usize, j: usize) -> Option<&T> {
+        if i >= self.n || j >= self.n {
+            return None;
+        }
+        match self.position(i, j) {
+            Some(pos) => self.data.get(pos),
+            None => self.diagonal.as_ref(),
+        }
+    }
+
+    /// Gets the matrix entry by row and column label.
+    pub fn get_by_label(&self, row_label: &str, col_label: &str) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.get(self.index.get(row_label)?, self.index.get(col_label)?)
+    }
+
+    /// Returns the index of a row by its label.
+    pub fn row_index(&self, label: &str) -> Option<usize> {
+        self.index.get(label)
+    }
+
+    /// Returns the index of a column by its label.
+    pub fn col_index(&self, label: &str) -> Option<usize> {
+        self.index.get(label)
+    }
+
+    /// Returns the label of a row by its index.
+    pub fn row_label(&self, index: usize) -> &String {
+        &self.labels[index]
+    }
+
+    /// Returns the label of a column by its index.
+    pub fn col_label(&self, index: usize) -> &String {
+        &self.labels[index]
+    }
+
+    /// Returns the row labels, which are also the column labels.
+    pub fn row_labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    /// Returns the column labels, which are also the row labels.
+    pub fn col_labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    /// Returns the stored values: the upper triangle of the matrix, packed row-wise.
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    /// Consumes the matrix and returns its stored values, packed row-wise.
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+
+    /// Iterates over the values of the i-th row of the full matrix.
+    ///
+    /// # Panics
+    /// Panics if `i` is out of bounds.
+    pub fn row(&self, i: usize) -> impl Iterator<Item = &T> {
+        assert!(i < self.n, "row index {i} out of bounds for a matrix of {} rows", self.n);
+        (0..self.n).filter_map(move |j| self.get_ref(i, j))
+    }
+
+    /// Iterates over all the entries of the full matrix, row by row, as `(row, column, value)` triples.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, &T)> {
+        (0..self.n).flat_map(move |i| (0..self.n).filter_map(move |j| Some((i, j, self.get_ref(i, j)?))))
+    }
+
+    /// Checks whether the entry at (i, j) is missing.
+    ///
+    /// Diagonal entries of a matrix which does not store them are never missing.
+    pub fn is_missing(&self, i: usize, j: usize) -> bool {
+        if i >= self.n || j >= self.n {
+            return false;
+        }
+        match (&self.missing, self.position(i, j)) {
+            (Some(missing), Some(pos)) => missing[pos],
+            _ => false,
+        }
+    }
+
+    /// Counts the missing entries of the upper triangle of this matrix.
+    pub fn count_missing(&self) -> usize {
+        self.missing
+            .as_ref()
+            .map_or(0, |missing| missing.iter().filter(|&&m| m).count())
+    }
+
+    /// Position of the entry (i, j) in the packed storage; `None` for an implicit diagonal entry.
+    fn position(&self, i: usize, j: usize) -> Option<usize> {
+        packed_index(self.n, i, j, self.has_diagonal())
+    }
+}
+
+/// Number of values of the upper triangle of an `n` x `n` matrix.
+pub(crate) fn packed_len(n: usize, with_diagonal: bool) -> usize {
+    if with_diagonal {
+        n * (n + 1) / 2
+    } else {
+        n * n.saturating_sub(1) / 2
+    }
+}
+
+/// Position of the entry (i, j) or (j, i) in the upper triangle of an `n` x `n` matrix, packed row-wise.
+///
+/// Returns `None` for a diagonal entry when the diagonal is not stored.
+pub(crate) fn packed_index(n: usize, i: usize, j: usize, with_diagonal: bool) -> Option<usize> {
+    let (i, j) = if i <= j { (i, j) } else { (j, i) };
+    if with_diagonal {
+        // --- rows before i hold n, n-1, ..., n-i+1 values
+        Some(i * n - i * i.saturating_sub(1) / 2 - i + j)
+    } else if i == j {
+        None
+    } else {
+        Some(i * (n - 1) - i * i.saturating_sub(1) / 2 + j - i - 1)
+    }
+}
@@ -0,0 +1,206 @@
+//! Reading and writing distance matrices in the PHYLIP format.
+//!
+//! A PHYLIP file starts with the number of taxa, followed by one row per taxon: its name and
+//! the distances, either for all the taxa (square layout) or for the preceding taxa only
+//! (lower-triangular layout):
+//! ```text
+//!     3
+//! Alice  0.0 1.5 2.0
+//! Bob    1.5 0.0 2.2
+//! John   2.0 2.2 0.0
+//! ```
+//! In the *strict* variant a name occupies exactly the first 10 characters of a line and may contain spaces;
+//! in the *relaxed* variant a name is the first whitespace-delimited word.
+use std::io::{BufRead, Write};
+
+use std::fmt::Display;
+
+use crate::{DataMatrix, Element, Error};
+
+/// Width of a taxon name in the strict PHYLIP format.
+const STRICT_NAME_WIDTH: usize = 10;
+
+/// Row layouts of a PHYLIP file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Triangle {
+    Square,
+    LowerWithDiagonal,
+    Lower,
+}
+
+impl Triangle {
+    fn values_in_row(&self, n: usize, row: usize) -> usize {
+        match self {
+            Triangle::Square => n,
+            Triangle::LowerWithDiagonal => row + 1,
+            Triangle::Lower => row,
+        }
+    }
+}
+
+/// Reads a PHYLIP distance matrix; the layout (square or lower-triangular, with or without
+/// the diagonal) is detected from the number of values given for the first taxon.
+///
+/// Rows may be wrapped over several lines; blank lines are ignored, as is everything following the matrix.
+pub(crate) fn read_phylip<T: Element, R: BufRead>(reader: R, strict: bool) -> Result<DataMatrix<T>, Error> {
+    let mut lines = reader
+        .lines()
+        .enumerate()
+        .map(|(line_no, line)| line.map(|l| (line_no + 1, l)))
+        .filter(|line| !matches!(line, Ok((_, l)) if l.trim().is_empty()));
+
+    let Some(first) = lines.next() else {
+        return Err(Error::ParseError {
+            line: 0,
+            content: "empty PHYLIP input".to_string(),
+        });
+    };
+    let (line_no, first) = first?;
+    let n: usize = first
+        .split_whitespace()
+        .next()
+        .and_then(|token| token.parse().ok())
+        .ok_or_else(|| Error::ParseError {
+            line: line_no,
+            content: first.clone(),
+        })?;
+
+    // --- n is not trusted to preallocate memory until all the rows are read
+    let mut labels = Vec::new();
+    let mut rows = Vec::new();
+    let mut layout = Triangle::Square;
+    for row in 0..n {
+        let Some(line) = lines.next() else {
+            return Err(Error::IncorrectMatrixLabels {
+                expected: n,
+                actual: row,
+            });
+        };
+        let (row_line_no, line) = line?;
+        let (name, rest) = split_name(&line, strict);
+        let mut values: Vec<T> = parse_values(rest, row_line_no)?;
+        if row == 0 {
+            layout = match values.len() {
+                0 => Triangle::Lower,
+                1 if n > 1 => Triangle::LowerWithDiagonal,
+                _ => Triangle::Square,
+            };
+        }
+        let expected = layout.values_in_row(n, row);
+        // --- a long row may continue in the following lines
+        while values.len() < expected {
+            match lines.next() {
+                Some(next) => {
+                    let (next_no, next) = next?;
+                    values.extend(parse_values(&next, next_no)?);
+                }
+                None => {
+                    return Err(Error::NotEnoughColumns {
+                        line: row_line_no,
+                        needed: expected + 1,
+                        content: line.clone(),
+                    })
+                }
+            }
+        }
+        if values.len() > expected {
+            return Err(Error::TooManyColumns {
+                line: row_line_no,
+                content: line.clone(),
+            });
+        }
+        labels.push(name);
+        rows.push(values);
+    }
+
+    let data = if layout == Triangle::Square {
+        rows
+    } else {
+        let mut data = vec![vec![T::default(); n]; n];
+        for (i, row) in rows.into_iter().enumerate() {
+            for (j, value) in row.into_iter().enumerate() {
+                data[j][i] = value.clone();
+                data[i][j] = value;
+            }
+        }
+        data
+    };
+
+    for i in 0..n {
+        for j in 0..i {
+            if data[i][j] != data[j][i] {
+                return Err(Error::NotSymmetric {
+                    row: labels[i].clone(),
+                    col: labels[j].clone(),
+                    value: data[i][j].to_string(),
+                    transposed: data[j][i].to_string(),
+                });
+            }
+        }
+    }
+
+    DataMatrix::new(data, labels.clone(), labels)
+}
+
+/// Writes a square matrix in the PHYLIP format.
+pub(crate) fn write_phylip<T: Display>(
+    matrix: &DataMatrix<T>,
+    writer: &mut dyn Write,
+    strict: bool,
+    lower_triangle: bool,
+) -> Result<(), Error> {
+    if !matrix.is_square() {
+        return Err(Error::NotSquare {
+            nrows: matrix.nrows(),
+            ncols: matrix.ncols(),
+        });
+    }
+    writeln!(writer, "{:>5}", matrix.nrows())?;
+    for (i, row) in matrix.rows().enumerate() {
+        let label = matrix.row_label(i);
+        if strict {
+            if label.chars().count() > STRICT_NAME_WIDTH {
+                return Err(Error::LabelTooLong {
+                    label: label.clone(),
+                    max_length: STRICT_NAME_WIDTH,
+                });
+            }
+            write!(writer, "{:<width$}", label, width = STRICT_NAME_WIDTH)?;
+        } else {
+            write!(writer, "{}", label)?;
+        }
+        let n_values = if lower_triangle { i } else { row.len() };
+        for value in &row[..n_values] {
+            write!(writer, " {}", value)?;
+        }
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+/// Splits a row into the taxon name and the remaining text.
+fn split_name(line: &str, strict: bool) -> (String, &str) {
+    if strict {
+        match line.char_indices().nth(STRICT_NAME_WIDTH) {
+            Some((pos, _)) => (line[..pos].trim().to_string(), &line[pos..]),
+            None => (line.trim().to_string(), ""),
+        }
+    } else {
+        let line = line.trim_start();
+        match line.find(char::is_whitespace) {
+            Some(pos) => (line[..pos].to_string(), &line[pos..]),
+            None => (line.to_string(), ""),
+        }
+    }
+}
+
+fn parse_values<T: Element>(text: &str, line_no: usize) -> Result<Vec<T>, Error> {
+    text.split_whitespace()
+        .map(|token| {
+            token.parse().map_err(|_| Error::ParseError {
+                line: line_no,
+                content: token.to_string(),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,156 @@
+//! Reordering rows and columns of a [`DataMatrix`], and aligning two matrices to the same labels.
+use std::collections::HashSet;
+
+use crate::{DataMatrix, Error};
+
+/// Defines which labels [`DataMatrix::align()`] keeps.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Alignment<T = f64> {
+    /// Keep the labels present in both matrices
+    Intersection,
+    /// Keep the labels present in any of the matrices; entries a matrix lacks hold the `fill` value
+    /// and are marked as [missing](DataMatrix::is_missing)
+    Union { fill: T },
+}
+
+impl<T: Clone> DataMatrix<T> {
+    /// Reorders rows and columns of this matrix: the i-th row of the result is the `rows[i]`-th row
+    /// of this matrix, and the j-th column is the `cols[j]`-th column.
+    ///
+    /// Returns [`Error::InvalidPermutation`] unless each row and each column is given once;
+    /// an index given twice results in [`Error::DuplicateLabel`], an index out of bounds in
+    /// [`Error::IndexOutOfBounds`]. The matrix is left unchanged on error.
+    pub fn permute(&mut self, rows: &[usize], cols: &[usize]) -> Result<(), Error> {
+        for (expected, actual) in [(self.nrows(), rows.len()), (self.ncols(), cols.len())] {
+            if expected != actual {
+                return Err(Error::InvalidPermutation { expected, actual });
+            }
+        }
+        *self = self.select_indices(rows, cols)?;
+        Ok(())
+    }
+
+    /// Sorts rows and columns of this matrix by their labels.
+    ///
+    /// # Example
+    /// ```rust
+    /// use data_matrix::{DataMatrixBuilder, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let mut matrix = DataMatrixBuilder::new().from_str("b y 1.0\nb x 2.0\na y 3.0\na x 4.0\n")?;
+    /// assert_eq!(matrix.row_labels(), ["b", "a"]);
+    /// matrix.sort_labels();
+    /// assert_eq!(matrix.row_labels(), ["a", "b"]);
+    /// assert_eq!(matrix.col_labels(), ["x", "y"]);
+    /// assert_eq!(matrix.as_slice(), &[4.0, 3.0, 2.0, 1.0]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn sort_labels(&mut self) {
+        if self.nrows() == 0 || self.ncols() == 0 {
+            return;
+        }
+        let sorted = |labels: &[String]| {
+            let mut order: Vec<usize> = (0..labels.len()).collect();
+            order.sort_by(|&a, &b| labels[a].cmp(&labels[b]));
+            order
+        };
+        let (rows, cols) = (sorted(self.row_labels()), sorted(self.col_labels()));
+        self.permute(&rows, &cols).expect("sorted indices are a permutation of rows and columns");
+    }
+
+    /// Reorders rows and columns of this matrix to follow the labels of the `other` matrix.
+    ///
+    /// Both matrices must have the same row labels and the same column labels. Returns [`Error::UnknownLabel`]
+    /// for a label of `other` this matrix doesn't have, and [`Error::InvalidPermutation`] when `other` lacks
+    /// some of the labels of this matrix.
+    pub fn reorder_like<U>(&mut self, other: &DataMatrix<U>) -> Result<(), Error> {
+        let index = |labels: &[String], lookup: &dyn Fn(&str) -> Option<usize>| {
+            labels
+                .iter()
+                .map(|label| lookup(label).ok_or_else(|| Error::UnknownLabel { label: label.clone() }))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let rows = index(other.row_labels(), &|label| self.row_index(label))?;
+        let cols = index(other.col_labels(), &|label| self.col_index(label))?;
+        self.permute(&rows, &cols)
+    }
+
+    /// Creates copies of two matrices which share the same row labels and the same column labels,
+    /// in the same order, so they can be compared entry by entry.
+    ///
+    /// Labels follow their order in `a`; with [`Alignment::Union`], labels only `b` has come next,
+    /// in their order in `b`. Returns [`Error::EmptySelection`] if the matrices have no row or no column
+    /// label in common.
+    ///
+    /// # Example
+    /// ```rust
+    /// use data_matrix::{Alignment, DataMatrix, DataMatrixBuilder, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let a = DataMatrixBuilder::new().symmetric(true).from_str("A B 1.0\nA C 2.0\nB C 3.0\n")?;
+    /// let b = DataMatrixBuilder::new().symmetric(true).from_str("C B 3.5\nB D 4.0\n")?;
+    ///
+    /// let (a1, b1) = DataMatrix::align(&a, &b, Alignment::Intersection)?;
+    /// assert_eq!(a1.row_labels(), ["B", "C"]);
+    /// assert_eq!(b1.row_labels(), ["B", "C"]);
+    /// assert_eq!((a1[("B", "C")], b1[("B", "C")]), (3.0, 3.5));
+    ///
+    /// let (a2, b2) = DataMatrix::align(&a, &b, Alignment::Union { fill: f64::NAN })?;
+    /// assert_eq!(a2.col_labels(), ["A", "B", "C", "D"]);
+    /// assert!(a2.is_missing(0, 3) && a2[("A", "D")].is_nan());
+    /// assert_eq!(b2[("D", "B")], 4.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn align(a: &Self, b: &Self, alignment: Alignment<T>) -> Result<(Self, Self), Error> {
+        match alignment {
+            Alignment::Intersection => {
+                let rows = intersection(a.row_labels(), b.row_labels());
+                let cols = intersection(a.col_labels(), b.col_labels());
+                Ok((a.select(&rows, &cols)?, b.select(&rows, &cols)?))
+            }
+            Alignment::Union { fill } => {
+                let rows = union(a.row_labels(), b.row_labels());
+                let cols = union(a.col_labels(), b.col_labels());
+                Ok((a.padded(&rows, &cols, &fill)?, b.padded(&rows, &cols, &fill)?))
+            }
+        }
+    }
+
+    /// Creates a matrix of the given labels; entries this matrix lacks are missing and hold the `fill` value.
+    fn padded(&self, rows: &[&str], cols: &[&str], fill: &T) -> Result<Self, Error> {
+        let col_sources: Vec<Option<usize>> = cols.iter().map(|label| self.col_index(label)).collect();
+        let mut data = Vec::with_capacity(rows.len() * cols.len());
+        let mut missing = Vec::with_capacity(rows.len() * cols.len());
+        for row in rows {
+            let i = self.row_index(row);
+            for &j in &col_sources {
+                match i.zip(j) {
+                    Some((i, j)) => {
+                        data.push(self.row(i)[j].clone());
+                        missing.push(self.is_missing(i, j));
+                    }
+                    None => {
+                        data.push(fill.clone());
+                        missing.push(true);
+                    }
+                }
+            }
+        }
+        let owned = |labels: &[&str]| labels.iter().map(|label| label.to_string()).collect();
+        Ok(Self::from_vec(data, owned(rows), owned(cols))?.with_missing(missing))
+    }
+}
+
+/// Labels of `a` which `b` has as well, in the order of `a`.
+fn intersection<'a>(a: &'a [String], b: &[String]) -> Vec<&'a str> {
+    let b: HashSet<&str> = b.iter().map(String::as_str).collect();
+    a.iter().map(String::as_str).filter(|label| b.contains(label)).collect()
+}
+
+/// Labels of `a`, followed by labels of `b` which `a` doesn't have.
+fn union<'a>(a: &'a [String], b: &'a [String]) -> Vec<&'a str> {
+    let mut seen: HashSet<&str> = a.iter().map(String::as_str).collect();
+    let mut labels: Vec<&str> = a.iter().map(String::as_str).collect();
+    labels.extend(b.iter().map(String::as_str).filter(|label| seen.insert(label)));
+    labels
+}
@@ -0,0 +1,144 @@
+//! Serde support for [`DataMatrix`], available with the `serde` feature.
+//!
+//! A matrix is serialized as a struct of its labels and values packed row-wise:
+//! `{row_labels, col_labels, values}`. A symmetric matrix, i.e. a square one which rows and columns
+//! share the same labels, is stored in a compact form holding each pair of entries once:
+//! `{labels, upper_triangle}`, where the upper triangle is packed row-wise with the diagonal.
+//! Both forms may have a `missing` field, which lists positions of missing entries in `values`
+//! or `upper_triangle`; it's omitted when no entry is missing.
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::DataMatrix;
+
+/// The upper triangle of a symmetric matrix, serialized row by row
+struct UpperTriangle<'a, T>(&'a DataMatrix<T>);
+
+impl<T: Serialize> Serialize for UpperTriangle<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let matrix = self.0;
+        serializer.collect_seq((0..matrix.nrows()).flat_map(|i| &matrix.row(i)[i..]))
+    }
+}
+
+impl<T: PartialEq> DataMatrix<T> {
+    /// Checks whether this matrix can be serialized in the compact symmetric form.
+    fn has_symmetric_form(&self) -> bool {
+        let n = self.nrows();
+        self.is_square()
+            && self.row_labels == self.col_labels
+            && (0..n).all(|i| {
+                (i + 1..n).all(|j| self.get_ref(i, j) == self.get_ref(j, i) && self.is_missing(i, j) == self.is_missing(j, i))
+            })
+    }
+}
+
+impl<T: Serialize + PartialEq> Serialize for DataMatrix<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let symmetric = self.has_symmetric_form();
+        let missing: Vec<usize> = if symmetric {
+            let upper = (0..self.nrows()).flat_map(|i| (i..self.ncols()).map(move |j| (i, j)));
+            upper.enumerate().filter(|&(_, (i, j))| self.is_missing(i, j)).map(|(k, _)| k).collect()
+        } else {
+            let n_values = self.nrows() * self.ncols();
+            (0..n_values).filter(|&k| self.is_missing(k / self.ncols(), k % self.ncols())).collect()
+        };
+
+        let n_fields = if symmetric { 2 } else { 3 } + usize::from(!missing.is_empty());
+        let mut state = serializer.serialize_struct("DataMatrix", n_fields)?;
+        if symmetric {
+            state.serialize_field("labels", &self.row_labels)?;
+            state.serialize_field("upper_triangle", &UpperTriangle(self))?;
+        } else {
+            state.serialize_field("row_labels", &self.row_labels)?;
+            state.serialize_field("col_labels", &self.col_labels)?;
+            state.serialize_field("values", &self.data)?;
+        }
+        if missing.is_empty() {
+            state.skip_field("missing")?;
+        } else {
+            state.serialize_field("missing", &missing)?;
+        }
+        state.end()
+    }
+}
+
+/// Fields of both the full and the compact symmetric form of a serialized matrix
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SerializedMatrix<T> {
+    labels: Option<Vec<String>>,
+    upper_triangle: Option<Vec<T>>,
+    row_labels: Option<Vec<String>>,
+    col_labels: Option<Vec<String>>,
+    values: Option<Vec<T>>,
+    #[serde(default)]
+    missing: Vec<usize>,
+}
+
+impl<'de, T: Deserialize<'de> + Clone> Deserialize<'de> for DataMatrix<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let serialized = SerializedMatrix::<T>::deserialize(deserializer)?;
+        let (values, row_labels, col_labels, missing) = match serialized {
+            SerializedMatrix {
+                labels: Some(labels),
+                upper_triangle: Some(upper),
+                row_labels: None,
+                col_labels: None,
+                values: None,
+                missing,
+            } => {
+                let n = labels.len();
+                if upper.len() != n * (n + 1) / 2 {
+                    return Err(de::Error::invalid_length(upper.len(), &"the upper triangle of a square matrix of the given labels"));
+                }
+                let (values, missing) = unpack_upper(upper, n, &missing)?;
+                (values, labels.clone(), labels, missing)
+            }
+            SerializedMatrix {
+                labels: None,
+                upper_triangle: None,
+                row_labels: Some(row_labels),
+                col_labels: Some(col_labels),
+                values: Some(values),
+                missing,
+            } => {
+                let flags = missing_flags(&missing, values.len())?;
+                (values, row_labels, col_labels, flags)
+            }
+            _ => {
+                return Err(de::Error::custom(
+                    "expected either `labels` and `upper_triangle`, or `row_labels`, `col_labels` and `values`",
+                ))
+            }
+        };
+
+        let matrix = DataMatrix::from_vec(values, row_labels, col_labels).map_err(de::Error::custom)?;
+        Ok(matrix.with_missing(missing))
+    }
+}
+
+/// Flags of missing entries from their positions among `n_values` values.
+fn missing_flags<E: de::Error>(positions: &[usize], n_values: usize) -> Result<Vec<bool>, E> {
+    let mut flags = vec![false; n_values];
+    for &k in positions {
+        *flags.get_mut(k).ok_or_else(|| E::custom(format!("missing entry {k} out of bounds of {n_values} values")))? = true;
+    }
+    Ok(flags)
+}
+
+/// Restores values and missing flags of an `n x n` symmetric matrix from its packed upper triangle.
+fn unpack_upper<T: Clone, E: de::Error>(upper: Vec<T>, n: usize, missing: &[usize]) -> Result<(Vec<T>, Vec<bool>), E> {
+    let upper_missing = missing_flags(missing, upper.len())?;
+    let mut cells: Vec<Option<T>> = vec![None; n * n];
+    let mut flags = vec![false; n * n];
+    let positions = (0..n).flat_map(|i| (i..n).map(move |j| (i, j)));
+    for ((value, is_missing), (i, j)) in upper.into_iter().zip(upper_missing).zip(positions) {
+        cells[j * n + i] = Some(value.clone());
+        flags[j * n + i] = is_missing;
+        cells[i * n + j] = Some(value);
+        flags[i * n + j] = is_missing;
+    }
+    let values = cells.into_iter().map(|value| value.expect("every cell is set by the upper triangle")).collect();
+    Ok((values, flags))
+}
@@ -0,0 +1,257 @@
+use crate::indexer::Indexer;
+use crate::DataMatrix;
+
+/// A matrix of values with labeled rows and columns, which stores only the entries actually given.
+///
+/// Entries are kept in the compressed sparse row (CSR) layout: column indices and values of stored
+/// entries, sorted by rows and columns, so the memory taken is proportional to the number of stored entries
+/// rather than to `nrows × ncols`. Any other entry reads as the fill value, which is the
+/// [missing value](crate::DataMatrixBuilder::missing_value) of the builder that loaded the matrix,
+/// and is reported as [missing](SparseDataMatrix::is_missing).
+///
+/// A sparse matrix is loaded with [`DataMatrixBuilder::from_file_sparse()`](crate::DataMatrixBuilder::from_file_sparse)
+/// or converted from a dense one with [`from_dense()`](SparseDataMatrix::from_dense).
+///
+/// # Example
+/// ```rust
+/// use data_matrix::{DataMatrixBuilder, Error};
+/// # fn main() -> Result<(), Error> {
+/// let contacts = DataMatrixBuilder::new()
+///     .symmetric(true)
+///     .from_reader_sparse("A B 1.5\nC D 2.0\n".as_bytes())?;
+/// assert_eq!(contacts.shape(), (4, 4));
+/// assert_eq!(contacts.nnz(), 4);
+/// assert_eq!(contacts.get_by_label("D", "C"), Some(2.0));
+/// assert_eq!(contacts.get_by_label("A", "D"), Some(0.0));
+/// assert!(contacts.is_missing(0, 3));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SparseDataMatrix<T = f64> {
+    /// Stored entries of the i-th row are found at `row_offsets[i]..row_offsets[i + 1]`.
+    row_offsets: Vec<usize>,
+
+    /// Column index of every stored entry.
+    col_indices: Vec<usize>,
+
+    /// Value of every stored entry.
+    values: Vec<T>,
+
+    /// The value of entries which are not stored.
+    fill: T,
+
+    /// Row labels (index -> label).
+    row_labels: Vec<String>,
+
+    /// Column labels (index -> label).
+    col_labels: Vec<String>,
+
+    /// Row label -> row index lookup.
+    row_index: Indexer,
+
+    /// Column label -> column index lookup.
+    col_index: Indexer,
+}
+
+impl<T> SparseDataMatrix<T> {
+    /// Creates a sparse matrix from `(row, column, value)` triples, sorted by rows and columns without repetitions.
+    pub(crate) fn from_triplets(
+        triplets: Vec<(usize, usize, T)>,
+        row_labels: Vec<String>,
+        col_labels: Vec<String>,
+        fill: T,
+    ) -> Self {
+        let nrows = row_labels.len();
+        let mut row_offsets = vec![0; nrows + 1];
+        let mut col_indices = Vec::with_capacity(triplets.len());
+        let mut values = Vec::with_capacity(triplets.len());
+        for (i, j, value) in triplets {
+            debug_assert!(i < nrows && j < col_labels.len());
+            row_offsets[i + 1] += 1;
+            col_indices.push(j);
+            values.push(value);
+        }
+        for i in 0..nrows {
+            row_offsets[i + 1] += row_offsets[i];
+        }
+
+        let row_index = Indexer::from_labels(&row_labels);
+        let col_index = Indexer::from_labels(&col_labels);
+        Self {
+            row_offsets,
+            col_indices,
+            values,
+            fill,
+            row_labels,
+            col_labels,
+            row_index,
+            col_index,
+        }
+    }
+
+    /// Converts a dense matrix into a sparse one.
+    ///
+    /// Entries of the dense matrix which are missing or equal to `fill` are not stored.
+    ///
+    /// # Example
+    /// ```rust
+    /// use data_matrix::{DataMatrix, SparseDataMatrix, Error};
+    /// # fn main() -> Result<(), Error> {
+    /// let labels = vec!["A".to_string(), "B".to_string()];
+    /// let dense = DataMatrix::from_vec(vec![0, 3, 0, 0], labels.clone(), labels)?;
+    /// let sparse = SparseDataMatrix::from_dense(&dense, 0);
+    /// assert_eq!(sparse.iter().collect::<Vec<_>>(), vec![(0, 1, &3)]);
+    /// assert_eq!(sparse.to_dense().as_slice(), dense.as_slice());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_dense(matrix: &DataMatrix<T>, fill: T) -> Self
+    where
+        T: Clone + PartialEq,
+    {
+        let mut triplets = Vec::new();
+        for (i, row) in matrix.rows().enumerate() {
+            for (j, value) in row.iter().enumerate() {
+                if *value != fill && !matrix.is_missing(i, j) {
+                    triplets.push((i, j, value.clone()));
+                }
+            }
+        }
+
+        Self::from_triplets(triplets, matrix.row_labels().to_vec(), matrix.col_labels().to_vec(), fill)
+    }
+
+    /// Converts this matrix into a dense one; entries which are not stored are marked as missing.
+    pub fn to_dense(&self) -> DataMatrix<T>
+    where
+        T: Clone,
+    {
+        let size = self.nrows() * self.ncols();
+        let mut data = vec![self.fill.clone(); size];
+        let mut missing = vec![true; size];
+        for (i, j, value) in self.iter() {
+            data[i * self.ncols() + j] = value.clone();
+            missing[i * self.ncols() + j] = false;
+        }
+        DataMatrix::from_vec(data, self.row_labels.clone(), self.col_labels.clone())
+            .expect("the shape of a sparse matrix is given by its labels")
+            .with_missing(missing)
+    }
+
+    /// Returns the number of rows.
+    pub fn nrows(&self) -> usize {
+        self.row_labels.len()
+    }
+
+    /// Returns the number of columns.
+    pub fn ncols(&self) -> usize {
+        self.col_labels.len()
+    }
+
+    /// Returns the number of rows and columns.
+    pub fn shape(&self) -> (usize, usize) {
+        (self.nrows(), self.ncols())
+    }
+
+    /// Returns the number of stored entries.
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns the value of entries which are not stored.
+    pub fn fill_value(&self) -> &T {
+        &self.fill
+    }
+
+    /// Gets the matrix entry at (i, j); the fill value is returned for an entry which is not stored.
+    pub fn get(&self, i: usize, j: usize) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.get_ref(i, j).cloned()
+    }
+
+    /// Gets a reference to the matrix entry at (i, j).
+    pub fn get_ref(&self, i: usize, j: usize) -> Option<&T> {
+        if i >= self.nrows() || j >= self.ncols() {
+            return None;
+        }
+        match self.position(i, j) {
+            Some(pos) => Some(&self.values[pos]),
+            None => Some(&self.fill),
+        }
+    }
+
+    /// Gets the matrix entry by row and column label.
+    pub fn get_by_label(&self, row_label: &str, col_label: &str) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.get(self.row_index.get(row_label)?, self.col_index.get(col_label)?)
+    }
+
+    /// Returns the index of a row by its label.
+    pub fn row_index(&self, label: &str) -> Option<usize> {
+        self.row_index.get(label)
+    }
+
+    /// Returns the index of a column by its label.
+    pub fn col_index(&self, label: &str) -> Option<usize> {
+        self.col_index.get(label)
+    }
+
+    /// Returns the label of a row by its index.
+    pub fn row_label(&self, index: usize) -> &String {
+        &self.row_labels[index]
+    }
+
+    /// Returns the label of a column by its index.
+    pub fn col_label(&self, index: usize) -> &String {
+        &self.col_labels[index]
+    }
+
+    /// Returns the row labels.
+    pub fn row_labels(&self) -> &[String] {
+        &self.row_labels
+    }
+
+    /// Returns the column labels.
+    pub fn col_labels(&self) -> &[String] {
+        &self.col_labels
+    }
+
+    /// Iterates over the stored entries of the i-th row as `(column, value)` pairs, by increasing column.
+    ///
+    /// # Panics
+    /// Panics if `i` is out of bounds.
+    pub fn row(&self, i: usize) -> impl Iterator<Item = (usize, &T)> {
+        assert!(i < self.nrows(), "row index {i} out of bounds for a matrix of {} rows", self.nrows());
+        let range = self.row_offsets[i]..self.row_offsets[i + 1];
+        self.col_indices[range.clone()].iter().copied().zip(&self.values[range])
+    }
+
+    /// Iterates over the stored entries, row by row, as `(row, column, value)` triples.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, &T)> {
+        (0..self.nrows()).flat_map(move |i| self.row(i).map(move |(j, value)| (i, j, value)))
+    }
+
+    /// Checks whether the entry at (i, j) is missing, i.e. not stored.
+    ///
+    /// Returns `false` when (i, j) is out of bounds.
+    pub fn is_missing(&self, i: usize, j: usize) -> bool {
+        i < self.nrows() && j < self.ncols() && self.position(i, j).is_none()
+    }
+
+    /// Counts the missing entries of this matrix, i.e. the entries which are not stored.
+    pub fn count_missing(&self) -> usize {
+        self.nrows() * self.ncols() - self.nnz()
+    }
+
+    /// Position of the stored entry (i, j) in `col_indices` and `values`.
+    fn position(&self, i: usize, j: usize) -> Option<usize> {
+        let start = self.row_offsets[i];
+        let cols = &self.col_indices[start..self.row_offsets[i + 1]];
+        cols.binary_search(&j).ok().map(|pos| start + pos)
+    }
+}
@@ -0,0 +1,118 @@
+#[cfg(test)]
+mod test_binary {
+    use data_matrix::{DataMatrix, DataMatrixBuilder, DataMatrixWriter, Error};
+
+    const CITIES: &str = "./tests/test_files/cities_by_distance.csv.gz";
+
+    fn cities() -> Result<DataMatrix, Error> {
+        DataMatrixBuilder::new().skip_header(true).index_columns(3, 4).symmetric(true).from_file(CITIES)
+    }
+
+    fn to_bytes(matrix: &DataMatrix) -> Result<Vec<u8>, Error> {
+        let mut buffer = Vec::new();
+        DataMatrixWriter::new().to_binary_writer(matrix, &mut buffer)?;
+        Ok(buffer)
+    }
+
+    #[test]
+    fn round_trip() -> Result<(), Error> {
+        let dm = cities()?;
+        let bytes = to_bytes(&dm)?;
+        assert_eq!(&bytes[0..8], b"DMATRIX\0");
+        let copy = DataMatrixBuilder::new().from_binary_reader(bytes.as_slice())?;
+        assert_eq!(copy.row_labels(), dm.row_labels());
+        assert_eq!(copy.col_labels(), dm.col_labels());
+        assert_eq!(copy.as_slice(), dm.as_slice());
+
+        // --- missing entries and integer values
+        let dm = DataMatrixBuilder::new().element_type::<i32>().from_str("a x 1\nb y -2\nb z 3\n")?;
+        let mut bytes = Vec::new();
+        DataMatrixWriter::new().to_binary_writer(&dm, &mut bytes)?;
+        let copy = DataMatrixBuilder::new().element_type::<i32>().from_binary_reader(bytes.as_slice())?;
+        assert_eq!(copy.as_slice(), dm.as_slice());
+        assert_eq!(copy.count_missing(), 3);
+        assert!(copy.is_missing(0, 2) && !copy.is_missing(1, 2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn compressed_file() -> Result<(), Error> {
+        let dm = cities()?;
+        let fname = std::env::temp_dir().join(format!("datamatrix_test_binary_{}.dmx.gz", std::process::id()));
+        DataMatrixWriter::new().to_binary_file(&dm, &fname)?;
+        let copy = DataMatrixBuilder::new().from_binary_file(&fname)?;
+        std::fs::remove_file(&fname)?;
+        assert_eq!(copy.as_slice(), dm.as_slice());
+
+        Ok(())
+    }
+
+    #[test]
+    fn foreign_byte_order() -> Result<(), Error> {
+        let dm = DataMatrix::from_vec(vec![1.5, -2.25], vec!["r".into()], vec!["a".into(), "b".into()])?;
+        let mut bytes = to_bytes(&dm)?;
+        let offset = u64::from_le_bytes(bytes[40..48].try_into().unwrap()) as usize;
+        let little_endian = bytes[10] == 0;
+        bytes[10] = if little_endian { 1 } else { 0 };
+        for value in bytes[offset..].chunks_exact_mut(8) {
+            value.reverse();
+        }
+        let copy = DataMatrixBuilder::new().from_binary_reader(bytes.as_slice())?;
+        assert_eq!(copy.as_slice(), &[1.5, -2.25]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn header_checks() -> Result<(), Error> {
+        let bytes = to_bytes(&cities()?)?;
+        let read = |bytes: &[u8]| DataMatrixBuilder::new().from_binary_reader(bytes);
+
+        let result = read(b"row col value\n");
+        assert!(matches!(result, Err(Error::InvalidBinaryFormat { .. })));
+        let mut newer = bytes.clone();
+        newer[8] = 2;
+        assert!(matches!(read(&newer), Err(Error::UnsupportedVersion { version: 2, supported: 1 })));
+        let result = read(&bytes[..bytes.len() - 1]);
+        assert!(matches!(result, Err(Error::InvalidBinaryFormat { .. })));
+        let mut overlapping = bytes.clone();
+        overlapping[40] = 32;
+        assert!(matches!(read(&overlapping), Err(Error::InvalidBinaryFormat { .. })));
+        // --- 2^32 x 2^29 values fit in a usize, but not their size in bytes
+        let mut oversized = bytes.clone();
+        oversized[16..24].copy_from_slice(&(1u64 << 32).to_le_bytes());
+        oversized[24..32].copy_from_slice(&(1u64 << 29).to_le_bytes());
+        assert!(matches!(read(&oversized), Err(Error::InvalidBinaryFormat { .. })));
+        let result = DataMatrixBuilder::new().element_type::<f32>().from_binary_reader(bytes.as_slice());
+        assert!(matches!(result, Err(Error::ElementTypeMismatch { expected: "f32", found: "f64" })));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "mmap")]
+    fn memory_mapped() -> Result<(), Error> {
+        use data_matrix::MappedDataMatrix;
+
+        let dm = DataMatrixBuilder::new().missing_value(-1.0).from_str("a x 1\nb y 2\nb z 3\n")?;
+        let fname = std::env::temp_dir().join(format!("datamatrix_test_mapped_{}.dmx", std::process::id()));
+        DataMatrixWriter::new().to_binary_file(&dm, &fname)?;
+        let mapped = MappedDataMatrix::<f64>::open(&fname)?;
+        assert_eq!(mapped.shape(), (2, 3));
+        assert_eq!(mapped.as_slice(), dm.as_slice());
+        assert_eq!(mapped.row(1), dm.row(1));
+        assert_eq!(mapped.get_by_label("b", "z"), Some(3.0));
+        assert_eq!(mapped.get(2, 0), None);
+        assert!(mapped.is_missing(0, 1) && !mapped.is_missing(1, 1));
+        assert_eq!(mapped.count_missing(), dm.count_missing());
+        assert_eq!(mapped.to_dense().as_slice(), dm.as_slice());
+
+        let result = MappedDataMatrix::<u32>::open(&fname);
+        assert!(matches!(result, Err(Error::ElementTypeMismatch { .. })));
+        drop(mapped);
+        std::fs::remove_file(&fname)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod test_compression {
+    use data_matrix::{DataMatrix, DataMatrixBuilder, DataMatrixWriter, Error, Layout};
+    use std::io::Cursor;
+
+    const CITIES: &str = "./tests/test_files/cities_by_distance.csv.gz";
+
+    fn load_cities(builder: DataMatrixBuilder) -> DataMatrixBuilder {
+        builder.skip_header(true).index_columns(3, 4).symmetric(true)
+    }
+
+    /// Writes a matrix into a file of the given name and loads it back
+    fn round_trip(dm: &DataMatrix, name: &str) -> Result<DataMatrix, Error> {
+        let output = std::env::temp_dir().join(format!("datamatrix_test_{}_{}", std::process::id(), name));
+        DataMatrixWriter::new().layout(Layout::Wide).to_file(dm, &output)?;
+        let copy = DataMatrixBuilder::new().from_wide_file(&output);
+        std::fs::remove_file(&output)?;
+        copy
+    }
+
+    #[test]
+    fn detected_regardless_of_extension() -> Result<(), Error> {
+        let misnamed = std::env::temp_dir().join(format!("datamatrix_test_gzipped_{}.csv", std::process::id()));
+        std::fs::copy(CITIES, &misnamed)?;
+        let dm = load_cities(DataMatrixBuilder::new()).from_file(&misnamed)?;
+        std::fs::remove_file(&misnamed)?;
+        assert_eq!(dm.nrows(), 15);
+
+        let bytes = std::fs::read(CITIES)?;
+        let dm = load_cities(DataMatrixBuilder::new().separator(',')).from_reader(Cursor::new(bytes))?;
+        assert_eq!(dm.nrows(), 15);
+
+        Ok(())
+    }
+
+    #[test]
+    fn plain_text_is_not_mistaken_for_bzip2() -> Result<(), Error> {
+        let dm = DataMatrixBuilder::new().from_str("BZh9 BZh1 1.5\n")?;
+        assert_eq!(dm.get_by_label("BZh9", "BZh1"), Some(1.5));
+
+        Ok(())
+    }
+
+    #[test]
+    fn compressed_round_trips() -> Result<(), Error> {
+        let dm = load_cities(DataMatrixBuilder::new()).from_file(CITIES)?;
+        let mut names = vec!["cities.tsv.gz"];
+        if cfg!(feature = "bzip2") {
+            names.push("cities.tsv.bz2");
+        }
+        if cfg!(feature = "xz") {
+            names.push("cities.tsv.xz");
+        }
+        if cfg!(feature = "zstd") {
+            names.push("cities.tsv.zst");
+        }
+        for name in names {
+            let copy = round_trip(&dm, name)?;
+            assert_eq!(dm.row_labels(), copy.row_labels());
+            assert_eq!(dm.to_rows(), copy.to_rows());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(not(feature = "zstd"))]
+    fn feature_required() {
+        let dm = DataMatrixBuilder::new().from_data(&[1.0]).unwrap();
+        let result = DataMatrixWriter::new().to_file(&dm, std::env::temp_dir().join(format!("datamatrix_test_{}.zst", std::process::id())));
+        assert!(matches!(result, Err(Error::IoError(e)) if e.kind() == std::io::ErrorKind::Unsupported));
+    }
+}
@@ -0,0 +1,171 @@
+#[cfg(test)]
+mod test_datamatrix {
+    use data_matrix::{DataMatrix, Error};
+
+    fn labels(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn label_lookup() -> Result<(), Error> {
+        let data = vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]];
+        let dm = DataMatrix::new(data, labels(&["r1", "r2"]), labels(&["c1", "c2", "c3"]))?;
+        assert_eq!(dm.row_index("r2"), Some(1));
+        assert_eq!(dm.col_index("c3"), Some(2));
+        assert_eq!(dm.row_index("c1"), None);
+        assert_eq!(dm.get_by_label("r2", "c1"), Some(4.0));
+        assert_eq!(dm.get_by_label("r1", "missing"), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn set_values() -> Result<(), Error> {
+        let mut dm = DataMatrix::from_vec(vec![0; 6], labels(&["r1", "r2"]), labels(&["c1", "c2", "c3"]))?;
+        dm.set(1, 2, 7)?;
+        dm.set_by_label("r1", "c2", 3)?;
+        *dm.get_mut(0, 0)? += 5;
+        dm.row_mut(1)[0] = -1;
+        assert_eq!(dm.as_slice(), &[5, 3, 0, -1, 0, 7]);
+
+        assert!(matches!(
+            dm.set(2, 0, 1),
+            Err(Error::IndexOutOfBounds {
+                row: 2,
+                col: 0,
+                nrows: 2,
+                ncols: 3
+            })
+        ));
+        assert!(matches!(dm.get_mut(0, 3), Err(Error::IndexOutOfBounds { .. })));
+        assert!(matches!(dm.set_by_label("r1", "c4", 1), Err(Error::UnknownLabel { label }) if label == "c4"));
+        assert!(matches!(dm.set_symmetric(0, 1, 1), Err(Error::NotSquare { .. })));
+        assert_eq!(dm.as_slice(), &[5, 3, 0, -1, 0, 7]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn symmetric_setter_and_missing_flags() -> Result<(), Error> {
+        let mut dm = data_matrix::DataMatrixBuilder::new().symmetric(true).from_str("a b 1.0\nb c 2.0\n")?;
+        assert_eq!(dm.count_missing(), 5);
+        dm.set_symmetric(0, 2, 4.0)?;
+        assert_eq!(dm.get_by_label("c", "a"), Some(4.0));
+        assert!(!dm.is_missing(0, 2) && !dm.is_missing(2, 0));
+        assert_eq!(dm.count_missing(), 3);
+        assert!(matches!(dm.set_symmetric(3, 0, 1.0), Err(Error::IndexOutOfBounds { .. })));
+
+        // --- writing through a reference or an index leaves missing flags alone
+        *dm.get_mut(1, 1)? = 9.0;
+        dm[(0, 0)] = 8.0;
+        assert!(dm.is_missing(1, 1) && dm.is_missing(0, 0));
+        dm.set(1, 1, 9.0)?;
+        assert!(!dm.is_missing(1, 1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn index_operators() -> Result<(), Error> {
+        let mut dm = DataMatrix::from_vec(vec![1.0, 2.0, 3.0, 4.0], labels(&["r1", "r2"]), labels(&["c1", "c2"]))?;
+        assert_eq!(dm[(1, 0)], 3.0);
+        assert_eq!(dm[("r1", "c2")], 2.0);
+        dm[(0, 0)] += 10.0;
+        dm[("r2", "c2")] = -4.0;
+        assert_eq!(dm.as_slice(), &[11.0, 2.0, 3.0, -4.0]);
+
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn index_out_of_bounds() {
+        let dm = DataMatrix::from_vec(vec![1.0, 2.0], labels(&["r1"]), labels(&["c1", "c2"])).unwrap();
+        let _ = dm[(1, 0)];
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown label: 'r2'")]
+    fn index_by_unknown_label() {
+        let mut dm = DataMatrix::from_vec(vec![1.0, 2.0], labels(&["r1"]), labels(&["c1", "c2"])).unwrap();
+        dm[("r2", "c1")] = 0.0;
+    }
+
+    #[test]
+    fn add_rows_and_columns() -> Result<(), Error> {
+        let mut dm = DataMatrix::from_vec(vec![1, 2, 3, 4, 5, 6], labels(&["r1", "r2"]), labels(&["c1", "c2", "c3"]))?;
+        dm.add_row("r3", 0)?;
+        dm.add_col("c4".to_string(), -1)?;
+        assert_eq!(dm.shape(), (3, 4));
+        assert_eq!(dm.as_slice(), &[1, 2, 3, -1, 4, 5, 6, -1, 0, 0, 0, -1]);
+        assert_eq!(dm.row_index("r3"), Some(2));
+        assert_eq!(dm.get_by_label("r2", "c4"), Some(-1));
+        assert_eq!(dm.count_missing(), 6);
+        assert!(dm.is_missing(2, 0) && dm.is_missing(0, 3) && !dm.is_missing(1, 2));
+        dm.set_by_label("r3", "c4", 9)?;
+        assert!(!dm.is_missing(2, 3));
+
+        assert!(matches!(dm.add_row("r1", 0), Err(Error::DuplicateLabel { label }) if label == "r1"));
+        assert!(matches!(dm.add_col("c2", 0), Err(Error::DuplicateLabel { .. })));
+        assert!(matches!(dm.add_taxon("x", 0), Err(Error::NotSquare { nrows: 3, ncols: 4 })));
+        assert_eq!(dm.shape(), (3, 4));
+
+        Ok(())
+    }
+
+    #[test]
+    fn remove_rows_and_columns() -> Result<(), Error> {
+        let mut dm = DataMatrix::from_vec((0..12).collect(), labels(&["r1", "r2", "r3"]), labels(&["c1", "c2", "c3", "c4"]))?;
+        dm.remove_row("r2")?;
+        dm.remove_col("c1")?;
+        assert_eq!(dm.shape(), (2, 3));
+        assert_eq!(dm.as_slice(), &[1, 2, 3, 9, 10, 11]);
+        assert_eq!(dm.row_labels(), ["r1", "r3"]);
+        assert_eq!(dm.row_index("r3"), Some(1));
+        assert_eq!(dm.col_index("c4"), Some(2));
+        assert_eq!(dm.col_index("c1"), None);
+        assert_eq!(dm.get_by_label("r3", "c2"), Some(9));
+
+        assert!(matches!(dm.remove_row("r2"), Err(Error::UnknownLabel { .. })));
+        assert!(matches!(dm.remove_taxon("r1"), Err(Error::UnknownLabel { label }) if label == "r1"));
+        assert_eq!(dm.shape(), (2, 3));
+
+        // --- the last row or column can't be removed, and the matrix is left unchanged
+        dm.remove_row("r1")?;
+        assert!(matches!(dm.remove_row("r3"), Err(Error::EmptySelection { nrows: 0, ncols: 3 })));
+        dm.remove_col("c2")?;
+        dm.remove_col("c3")?;
+        assert!(matches!(dm.remove_col("c4"), Err(Error::EmptySelection { nrows: 1, ncols: 0 })));
+        assert_eq!(dm.shape(), (1, 1));
+        assert_eq!(dm.as_slice(), &[11]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn grow_distance_matrix() -> Result<(), Error> {
+        let mut dm = data_matrix::DataMatrixBuilder::new().symmetric(true).from_str("a b 1.0\na c 2.0\nb c 3.0\n")?;
+        dm.add_taxon("d", 0.0)?;
+        assert_eq!(dm.row_labels(), dm.col_labels());
+        assert_eq!(dm.count_missing(), 3 + 7);
+        for other in ["a", "b", "c"] {
+            dm.set_by_label("d", other, 5.0)?;
+            dm.set_by_label(other, "d", 5.0)?;
+        }
+        assert_eq!(dm.row(3), &[5.0, 5.0, 5.0, 0.0]);
+        assert_eq!(dm.get_by_label("b", "d"), Some(5.0));
+
+        dm.remove_taxon("a")?;
+        assert_eq!(dm.shape(), (3, 3));
+        assert_eq!(dm.as_slice(), &[0.0, 3.0, 5.0, 3.0, 0.0, 5.0, 5.0, 5.0, 0.0]);
+        // --- the diagonal is not given in the input
+        assert_eq!(dm.count_missing(), 3);
+        assert!(dm.is_missing(2, 2) && !dm.is_missing(2, 0));
+        dm.remove_taxon("b")?;
+        dm.remove_taxon("c")?;
+        assert!(matches!(dm.remove_taxon("d"), Err(Error::EmptySelection { .. })));
+        assert_eq!(dm.row_labels(), ["d"]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod test_duplicates {
+    use data_matrix::{DataMatrixBuilder, DuplicatePolicy, Error};
+
+    const INPUT: &str = "./tests/test_files/three_columns_duplicated.txt";
+
+    #[test]
+    fn resolve_duplicates() -> Result<(), Error> {
+        for (policy, expected) in [
+            (DuplicatePolicy::KeepFirst, 1.2),
+            (DuplicatePolicy::KeepLast, 3.4),
+            (DuplicatePolicy::Min, 1.2),
+            (DuplicatePolicy::Max, 3.4),
+            (DuplicatePolicy::Sum, 4.6),
+        ] {
+            let dm = DataMatrixBuilder::new()
+                .symmetric(true)
+                .duplicates(policy)
+                .from_file(INPUT)?;
+            assert_eq!(dm.get_by_label("Alice", "Bob"), Some(expected));
+            assert_eq!(dm.get_by_label("Bob", "Alice"), Some(expected));
+            assert_eq!(dm.get_by_label("Bob", "John"), Some(2.4));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn not_symmetric_is_not_duplicated() -> Result<(), Error> {
+        let dm = DataMatrixBuilder::new()
+            .duplicates(DuplicatePolicy::Error)
+            .from_file(INPUT)?;
+        assert_eq!(dm.get_by_label("Alice", "Bob"), Some(1.2));
+        assert_eq!(dm.get_by_label("Bob", "Alice"), Some(3.4));
+
+        Ok(())
+    }
+
+    #[test]
+    fn conflict_reported() {
+        let result = DataMatrixBuilder::new()
+            .symmetric(true)
+            .duplicates(DuplicatePolicy::Error)
+            .from_file(INPUT);
+        match result {
+            Err(Error::ConflictingValues { row, col, first_value, second_value, .. }) => {
+                assert_eq!((row.as_str(), col.as_str()), ("Bob", "Alice"));
+                assert_eq!((first_value.as_str(), second_value.as_str()), ("1.2", "3.4"));
+            }
+            _ => panic!("conflicting values not detected"),
+        }
+    }
+
+    #[test]
+    fn equal_values_are_not_conflicting() -> Result<(), Error> {
+        // --- the file lists both (i, j) and (j, i) with identical values
+        let input = std::env::temp_dir().join(format!("datamatrix_test_both_directions_{}.txt", std::process::id()));
+        std::fs::write(&input, "A B 1.5\nB A 1.5\nA A 0\n").unwrap();
+        let dm = DataMatrixBuilder::new()
+            .symmetric(true)
+            .duplicates(DuplicatePolicy::Error)
+            .from_file(&input)?;
+        assert_eq!(dm.get_by_label("B", "A"), Some(1.5));
+        assert_eq!(dm.count_missing(), 1);
+
+        Ok(())
+    }
+}
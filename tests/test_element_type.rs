@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod test_element_type {
+    use data_matrix::{DataMatrix, DataMatrixBuilder, DataMatrixWriter, DuplicatePolicy, Element, Error, Layout};
+
+    #[test]
+    fn single_precision() -> Result<(), Error> {
+        let dm = DataMatrixBuilder::new()
+            .skip_header(true)
+            .index_columns(3, 4)
+            .symmetric(true)
+            .element_type::<f32>()
+            .from_file("./tests/test_files/cities_by_distance.csv.gz")?;
+        let err = dm.get_by_label("Tokyo", "New York City").unwrap() - 10851.73;
+        assert!(err.abs() < 0.01);
+
+        Ok(())
+    }
+
+    #[test]
+    fn integer_counts() -> Result<(), Error> {
+        let dm = DataMatrixBuilder::new()
+            .element_type::<u32>()
+            .duplicates(DuplicatePolicy::Sum)
+            .from_str("A B 2\nA B 3\nB A 1\n")?;
+        assert_eq!(dm.get_by_label("A", "B"), Some(5));
+        assert_eq!(dm.get_by_label("B", "A"), Some(1));
+        assert_eq!(dm.get_by_label("A", "A"), Some(0));
+
+        let result = DataMatrixBuilder::new().element_type::<u32>().from_str("A B 2.5\n");
+        assert!(matches!(result, Err(Error::ParseError { line: 1, .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn booleans_and_strings() -> Result<(), Error> {
+        let adjacency = DataMatrixBuilder::new()
+            .symmetric(true)
+            .element_type::<bool>()
+            .from_str("A B true\nB C true\n")?;
+        assert_eq!(adjacency.get_by_label("C", "B"), Some(true));
+        assert_eq!(adjacency.get_by_label("A", "C"), Some(false));
+
+        let annotations = DataMatrixBuilder::new()
+            .element_type::<String>()
+            .missing_value("-".to_string())
+            .duplicates(DuplicatePolicy::KeepFirst)
+            .from_str("G1 S1 up\nG1 S2 down\nG1 S1 flat\nG2 S2 NA\n")?;
+        assert_eq!(annotations.get_ref(0, 0).map(String::as_str), Some("up"));
+        assert_eq!(annotations.get_by_label("G2", "S2").as_deref(), Some("-"));
+        assert!(annotations.is_missing(1, 1));
+
+        let result = DataMatrixBuilder::new()
+            .element_type::<String>()
+            .duplicates(DuplicatePolicy::Sum)
+            .from_str("G1 S1 up\nG1 S1 down\n");
+        assert!(matches!(result, Err(Error::UnsupportedPolicy { policy: DuplicatePolicy::Sum, .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn integer_aggregates() -> Result<(), Error> {
+        // --- 201 duplicates of a cell, which sum up to 1: their count does not fit in i8, but their mean does
+        let text: String = (0..201).map(|k| format!("A B {}\n", if k % 2 == 0 { 1 } else { -1 })).collect();
+        let dm = DataMatrixBuilder::new()
+            .element_type::<i8>()
+            .duplicates(DuplicatePolicy::Average)
+            .from_str(&text)?;
+        assert_eq!(dm.get_by_label("A", "B"), Some(0));
+
+        let average = |text: &str| DataMatrixBuilder::new().element_type::<u8>().duplicates(DuplicatePolicy::Average).from_str(text);
+        assert_eq!(average("A B 100\nA B 150\n")?.get_by_label("A", "B"), Some(125));
+        assert!(matches!(average("A B 100\nA B 150\nA B 10\n"), Err(Error::ValueOverflow { .. })));
+
+        let result = DataMatrixBuilder::new()
+            .element_type::<i8>()
+            .duplicates(DuplicatePolicy::Sum)
+            .from_reader_sparse("A B -100\nA B -100\n".as_bytes());
+        assert!(matches!(result, Err(Error::ValueOverflow { policy: DuplicatePolicy::Sum, .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn integer_round_trip() -> Result<(), Error> {
+        let data = vec![vec![1i64, -2], vec![30, 4]];
+        let dm = DataMatrix::new(data, vec!["a".into(), "b".into()], vec!["x".into(), "y".into()])?;
+        let mut buffer: Vec<u8> = Vec::new();
+        DataMatrixWriter::new().layout(Layout::Wide).to_writer(&dm, &mut buffer)?;
+        let copy = DataMatrixBuilder::new()
+            .element_type::<i64>()
+            .from_wide_reader(buffer.as_slice())?;
+        assert_eq!(dm.to_rows(), copy.to_rows());
+
+        Ok(())
+    }
+
+    /// A user type, parsed from a 'chromosome:position' text
+    #[derive(Clone, Default, PartialEq, Debug)]
+    struct Locus {
+        chromosome: String,
+        position: u64,
+    }
+
+    impl std::str::FromStr for Locus {
+        type Err = ();
+        fn from_str(s: &str) -> Result<Self, ()> {
+            let (chromosome, position) = s.split_once(':').ok_or(())?;
+            Ok(Locus { chromosome: chromosome.to_string(), position: position.parse().map_err(|_| ())? })
+        }
+    }
+
+    impl std::fmt::Display for Locus {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{}:{}", self.chromosome, self.position)
+        }
+    }
+
+    impl Element for Locus {}
+
+    #[test]
+    fn user_type() -> Result<(), Error> {
+        let dm = DataMatrixBuilder::new()
+            .element_type::<Locus>()
+            .duplicates(DuplicatePolicy::KeepFirst)
+            .from_str("a x chr1:100\nb x chr2:5\na x chr3:1\n")?;
+        assert_eq!(dm.get_by_label("a", "x"), Some(Locus { chromosome: "chr1".into(), position: 100 }));
+        assert_eq!(dm.get_by_label("b", "x").map(|locus| locus.position), Some(5));
+
+        Ok(())
+    }
+}
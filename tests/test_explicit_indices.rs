@@ -0,0 +1,53 @@
+#[cfg(test)]
+mod test_explicit_indices {
+    use data_matrix::{DataMatrix, DataMatrixBuilder, Error};
+
+    fn load(name: &str, content: &str) -> Result<DataMatrix, Error> {
+        let input = std::env::temp_dir().join(format!("datamatrix_test_{}_{}.txt", std::process::id(), name));
+        std::fs::write(&input, content).unwrap();
+        DataMatrixBuilder::new()
+            .symmetric(true)
+            .index_columns(2, 3)
+            .data_column(4)
+            .from_file(&input)
+    }
+
+    #[test]
+    fn label_with_two_indices() {
+        let result = load("two_indices", "A B 0 1 1.0\nB C 2 2 2.0\n");
+        match result {
+            Err(Error::ConflictingIndex { label, first_index, second_index, line }) => {
+                assert_eq!(label, "B");
+                assert_eq!((first_index, second_index, line), (1, 2, 2));
+            }
+            _ => panic!("conflicting index not detected"),
+        }
+    }
+
+    #[test]
+    fn index_collision() {
+        let result = load("collision", "A B 0 1 1.0\nC A 1 0 2.0\n");
+        match result {
+            Err(Error::IndexCollision { index, first_label, second_label, line }) => {
+                assert_eq!((first_label.as_str(), second_label.as_str()), ("B", "C"));
+                assert_eq!((index, line), (1, 2));
+            }
+            _ => panic!("index collision not detected"),
+        }
+    }
+
+    #[test]
+    fn gap_in_indices() {
+        let result = load("gap", "A B 0 1 1.0\nB C 1 5 2.0\n");
+        assert!(matches!(result, Err(Error::NonContiguousIndices { missing: 2, max_index: 5 })));
+    }
+
+    #[test]
+    fn consistent_indices() -> Result<(), Error> {
+        let dm = load("consistent", "C B 2 1 1.0\nB A 1 0 2.0\nA C 0 2 3.0\n")?;
+        assert_eq!(dm.row_labels(), ["A", "B", "C"]);
+        assert_eq!(dm.get_by_label("C", "A"), Some(3.0));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+//! Property tests: no input, however malformed, may cause a panic while loading a matrix.
+#[cfg(test)]
+mod test_fuzz {
+    use data_matrix::{DataMatrixBuilder, DuplicatePolicy, Error};
+    use proptest::prelude::*;
+    use std::path::PathBuf;
+
+    fn write_input(name: &str, content: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("datamatrix_fuzz_{}_{}.txt", std::process::id(), name));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    /// Runs every reader of the crate on the given file; results are ignored, panics are not.
+    fn load_all_ways(path: &PathBuf) {
+        let _ = DataMatrixBuilder::new().from_file(path);
+        let _ = DataMatrixBuilder::new()
+            .symmetric(true)
+            .duplicates(DuplicatePolicy::Average)
+            .from_file(path);
+        let _ = DataMatrixBuilder::new()
+            .symmetric(true)
+            .index_columns(2, 3)
+            .data_column(4)
+            .skip_header(true)
+            .from_file(path);
+        let _ = DataMatrixBuilder::new()
+            .index_columns(3, 4)
+            .duplicates(DuplicatePolicy::Error)
+            .separator(',')
+            .from_file(path);
+        let _ = DataMatrixBuilder::new().labels(["A", "B"]).data_column(1).from_file(path);
+        let _ = DataMatrixBuilder::new().na_tokens(["", "-"]).from_wide_file(path);
+        let _ = DataMatrixBuilder::new().from_phylip(path);
+        let _ = DataMatrixBuilder::new().strict_phylip_names(true).from_phylip(path);
+        let _ = DataMatrixBuilder::new().from_matrix_market(path);
+    }
+
+    /// Lines built of tokens that look like labels, numbers or indices.
+    fn table_like() -> impl Strategy<Value = String> {
+        let token = prop_oneof![
+            Just("A".to_string()),
+            Just("B".to_string()),
+            Just("C".to_string()),
+            Just("NA".to_string()),
+            Just(String::new()),
+            (0usize..5).prop_map(|i| i.to_string()),
+            any::<f64>().prop_map(|v| v.to_string()),
+            "[ -~]{0,12}",
+        ];
+        let separator = prop_oneof![Just(" "), Just(","), Just("\t"), Just("  ")];
+        let line = (prop::collection::vec(token, 0..7), separator)
+            .prop_map(|(tokens, sep)| tokens.join(sep));
+        let line = prop_oneof![8 => line, 1 => Just("# comment".to_string()), 1 => (0usize..20).prop_map(|n| n.to_string())];
+        prop::collection::vec(line, 0..12).prop_map(|lines| lines.join("\n"))
+    }
+
+    /// Matrix Market files with small entries but any size line, including absurd ones.
+    fn market_like() -> impl Strategy<Value = String> {
+        let banner = prop_oneof![
+            Just("%%MatrixMarket matrix coordinate real general"),
+            Just("%%MatrixMarket matrix coordinate integer symmetric"),
+            Just("%%matrixmarket MATRIX coordinate real general"),
+            Just("%%MatrixMarket matrix array real general"),
+        ];
+        let size = prop_oneof![
+            4 => (0usize..5).prop_map(|n| n.to_string()),
+            2 => ((1usize << 61)..=usize::MAX).prop_map(|n| n.to_string()),
+            1 => Just("18446744073709551616".to_string()),
+            1 => Just("-1".to_string()),
+        ];
+        // --- the number of entries given in the size line usually matches the entries of the file
+        let size_line = prop_oneof![
+            4 => (size.clone(), size.clone()).prop_map(|(nrows, ncols)| format!("{nrows} {ncols} {{n}}")),
+            1 => prop::collection::vec(size, 0..5).prop_map(|tokens| tokens.join(" ")),
+        ];
+        let labels = prop_oneof![
+            Just(String::new()),
+            Just("% row_labels: a b\n".to_string()),
+            Just("% row_labels: a b c\n% col_labels: x y\n".to_string()),
+        ];
+        let entry = (1usize..4, 1usize..4, prop_oneof![Just("1.5".to_string()), Just("-2".to_string()), Just("x".to_string())])
+            .prop_map(|(i, j, value)| format!("{i} {j} {value}"));
+        (banner, labels, size_line, prop::collection::vec(entry, 0..6))
+            .prop_map(|(banner, labels, size, entries)| {
+                let size = size.replace("{n}", &entries.len().to_string());
+                format!("{banner}\n{labels}{size}\n{}\n", entries.join("\n"))
+            })
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(200))]
+
+        #[test]
+        fn arbitrary_text_does_not_panic(content in "\\PC{0,200}") {
+            let path = write_input("arbitrary_text", &content);
+            load_all_ways(&path);
+        }
+
+        #[test]
+        fn table_like_text_does_not_panic(content in table_like()) {
+            let path = write_input("table_like_text", &content);
+            load_all_ways(&path);
+        }
+
+        #[test]
+        fn matrix_market_does_not_panic(content in market_like()) {
+            let _ = DataMatrixBuilder::new().from_matrix_market_reader(content.as_bytes());
+            let _ = DataMatrixBuilder::new()
+                .symmetric(true)
+                .element_type::<i32>()
+                .duplicates(DuplicatePolicy::Average)
+                .from_matrix_market_reader(content.as_bytes());
+            let path = write_input("matrix_market", &content);
+            let _ = DataMatrixBuilder::new().from_matrix_market_sparse(&path);
+            let _ = DataMatrixBuilder::new().duplicates(DuplicatePolicy::Error).from_matrix_market_sparse(&path);
+        }
+    }
+
+    #[test]
+    fn physical_line_numbers() {
+        let path = write_input("line_numbers", "# header comment\n\nA B 1.0\n# another comment\nB\n");
+        let result = DataMatrixBuilder::new().from_file(&path);
+        assert!(matches!(result, Err(Error::NotEnoughColumns { line: 5, needed: 3, .. })));
+
+        let path = write_input("line_numbers_value", "# comment\nA B 1.0\n\nA C x\n");
+        let result = DataMatrixBuilder::new().from_file(&path);
+        assert!(matches!(result, Err(Error::ParseError { line: 4, .. })));
+
+        let path = write_input("line_numbers_index", "A B 0 1 1.0\n# comment\nB A 1 zero 1.0\n");
+        let result = DataMatrixBuilder::new().index_columns(2, 3).data_column(4).from_file(&path);
+        assert!(matches!(result, Err(Error::ParseError { line: 3, .. })));
+    }
+}
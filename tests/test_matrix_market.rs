@@ -0,0 +1,176 @@
+#[cfg(test)]
+mod test_matrix_market {
+    use data_matrix::{DataMatrix, DataMatrixBuilder, DataMatrixWriter, DuplicatePolicy, Error, Layout};
+
+    const GENERAL: &str = "%%MatrixMarket matrix coordinate real general
+% written by hand
+%
+2 3 4
+1 1 1.5
+1 3 -2
+2 2 4e-1
+2 3 7
+";
+
+    #[test]
+    fn general_matrix() -> Result<(), Error> {
+        let dm = DataMatrixBuilder::new().from_matrix_market_reader(GENERAL.as_bytes())?;
+        assert_eq!(dm.shape(), (2, 3));
+        assert_eq!(dm.row_labels(), ["row-1", "row-2"]);
+        assert_eq!(dm.col_labels(), ["col-1", "col-2", "col-3"]);
+        assert_eq!(dm.as_slice(), &[1.5, 0.0, -2.0, 0.0, 0.4, 7.0]);
+        assert_eq!(dm.count_missing(), 2);
+        assert!(dm.is_missing(0, 1) && dm.is_missing(1, 0));
+
+        // --- labels given to the builder, integer values
+        let dm = DataMatrixBuilder::new()
+            .element_type::<i32>()
+            .labels(["a", "b", "c"])
+            .from_matrix_market_reader("%%MatrixMarket matrix coordinate integer general\n3 3 1\n3 1 5\n".as_bytes())?;
+        assert_eq!(dm.get_by_label("c", "a"), Some(5));
+        assert_eq!(dm.get_by_label("a", "c"), Some(0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn symmetric_matrix() -> Result<(), Error> {
+        let dm = DataMatrixBuilder::new().from_matrix_market("./tests/test_files/symmetric.mtx")?;
+        assert_eq!(dm.row_labels(), ["Alice", "Bob", "John"]);
+        assert_eq!(dm.col_labels(), dm.row_labels());
+        assert_eq!(dm.get_by_label("Bob", "John"), Some(2.2));
+        assert_eq!(dm.get_by_label("John", "Bob"), Some(2.2));
+        assert_eq!(dm.count_missing(), 3);
+
+        let sparse = DataMatrixBuilder::new().from_matrix_market_sparse("./tests/test_files/symmetric.mtx")?;
+        assert_eq!(sparse.nnz(), 6);
+        assert_eq!(sparse.get_by_label("Alice", "Bob"), Some(1.5));
+
+        // --- a general file read as symmetric, where an entry is given in both triangles
+        let text = "%%MatrixMarket matrix coordinate real general\n2 2 3\n1 2 1.0\n2 1 3.0\n2 2 5.0\n";
+        let dm = DataMatrixBuilder::new()
+            .symmetric(true)
+            .duplicates(DuplicatePolicy::Average)
+            .from_matrix_market_reader(text.as_bytes())?;
+        assert_eq!(dm.as_slice(), &[0.0, 2.0, 2.0, 5.0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip() -> Result<(), Error> {
+        let dm = DataMatrixBuilder::new().from_matrix_market_reader(GENERAL.as_bytes())?;
+        let mut buffer = Vec::new();
+        DataMatrixWriter::new()
+            .layout(Layout::MatrixMarket { symmetric: false })
+            .comment("two rows")
+            .to_writer(&dm, &mut buffer)?;
+        let text = String::from_utf8(buffer).unwrap();
+        assert!(text.starts_with("%%MatrixMarket matrix coordinate real general\n% two rows\n"));
+        assert!(text.contains("% col_labels: col-1 col-2 col-3\n2 3 4\n1 1 1.5\n"));
+        let copy = DataMatrixBuilder::new().from_matrix_market_reader(text.as_bytes())?;
+        assert_eq!(copy.as_slice(), dm.as_slice());
+        assert_eq!(copy.count_missing(), 2);
+
+        let dm = DataMatrixBuilder::new().from_matrix_market("./tests/test_files/symmetric.mtx")?;
+        let output = std::env::temp_dir().join(format!("datamatrix_test_round_trip_{}.mtx.gz", std::process::id()));
+        DataMatrixWriter::new().layout(Layout::MatrixMarket { symmetric: true }).to_file(&dm, &output)?;
+        let copy = DataMatrixBuilder::new().from_matrix_market(&output)?;
+        std::fs::remove_file(&output)?;
+        assert_eq!(copy.row_labels(), dm.row_labels());
+        assert_eq!(copy.as_slice(), dm.as_slice());
+        assert_eq!(copy.count_missing(), 3);
+
+        let general = DataMatrixBuilder::new().from_matrix_market_reader(GENERAL.as_bytes())?;
+        let result = DataMatrixWriter::new().layout(Layout::MatrixMarket { symmetric: true }).to_writer(&general, Vec::new());
+        assert!(matches!(result, Err(Error::NotSquare { .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn written_types_and_symmetry() -> Result<(), Error> {
+        let labels = |names: &[&str]| names.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let write = |layout, dm: &DataMatrix<i32>| {
+            let mut buffer = Vec::new();
+            DataMatrixWriter::new().layout(layout).to_writer(dm, &mut buffer).map(|_| String::from_utf8(buffer).unwrap())
+        };
+        let dm = DataMatrix::from_vec(vec![1, -2, -2, 3], labels(&["a", "b"]), labels(&["a", "b"]))?;
+        let text = write(Layout::MatrixMarket { symmetric: true }, &dm)?;
+        assert_eq!(text, "%%MatrixMarket matrix coordinate integer symmetric\n% row_labels: a b\n2 2 3\n1 1 1\n2 1 -2\n2 2 3\n");
+        let copy = DataMatrixBuilder::new().element_type::<i32>().from_matrix_market_reader(text.as_bytes())?;
+        assert_eq!(copy.as_slice(), dm.as_slice());
+
+        // --- values and missing flags of (i, j) and (j, i) must match
+        let asymmetric = DataMatrix::from_vec(vec![1, -2, 2, 3], labels(&["a", "b"]), labels(&["a", "b"]))?;
+        let result = write(Layout::MatrixMarket { symmetric: true }, &asymmetric);
+        assert!(matches!(result, Err(Error::NotSymmetric { row, col, value, transposed, .. })
+            if (row.as_str(), col.as_str(), value.as_str(), transposed.as_str()) == ("b", "a", "2", "-2")));
+        assert!(write(Layout::MatrixMarket { symmetric: false }, &asymmetric)?.starts_with("%%MatrixMarket matrix coordinate integer general\n"));
+        let partly_missing = DataMatrixBuilder::new().element_type::<i32>().from_str("a a 1\na b -2\nb b 3\n")?;
+        let result = write(Layout::MatrixMarket { symmetric: true }, &partly_missing);
+        assert!(matches!(result, Err(Error::NotSymmetric { value, transposed, .. }) if value == "missing" && transposed == "-2"));
+        let relabelled = DataMatrix::from_vec(vec![1, -2, -2, 3], labels(&["a", "b"]), labels(&["a", "c"]))?;
+        let result = write(Layout::MatrixMarket { symmetric: true }, &relabelled);
+        assert!(matches!(result, Err(Error::LabelMismatch { index: 1, .. })));
+
+        // --- values which are not numbers can't be read back from a Matrix Market file
+        let words = DataMatrix::from_vec(vec!["x".to_string()], labels(&["a"]), labels(&["b"]))?;
+        let result = DataMatrixWriter::new().layout(Layout::MatrixMarket { symmetric: false }).to_writer(&words, Vec::new());
+        assert!(matches!(result, Err(Error::UnsupportedElementType { format: "Matrix Market", .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn label_files() -> Result<(), Error> {
+        let dm = DataMatrixBuilder::new().from_matrix_market("./tests/test_files/symmetric.mtx")?;
+        let dir = std::env::temp_dir();
+        let pid = std::process::id();
+        let (rows, cols) = (dir.join(format!("datamatrix_test_rows_{pid}.txt")), dir.join(format!("datamatrix_test_cols_{pid}.txt.gz")));
+        DataMatrixWriter::new().to_label_files(&dm, &rows, &cols)?;
+        let text = "%%MatrixMarket matrix coordinate real general\n3 3 1\n1 3 4.5\n";
+        let copy = DataMatrixBuilder::new().label_files(&rows, &cols).from_matrix_market_reader(text.as_bytes())?;
+        std::fs::remove_file(&rows)?;
+        std::fs::remove_file(&cols)?;
+        assert_eq!(copy.row_labels(), dm.row_labels());
+        assert_eq!(copy.col_labels(), dm.col_labels());
+        assert_eq!(copy.get_by_label("Alice", "John"), Some(4.5));
+
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_input() {
+        let read = |text: &str| DataMatrixBuilder::new().from_matrix_market_reader(text.as_bytes());
+        let invalid = |text: &str| matches!(read(text), Err(Error::InvalidMatrixMarket { .. }));
+
+        assert!(invalid(""));
+        assert!(invalid("1 1 1\n1 1 1.0\n"));
+        assert!(invalid("%%MatrixMarket matrix array real general\n1 1\n1.0\n"));
+        assert!(invalid("%%MatrixMarket matrix coordinate complex general\n1 1 1\n1 1 1.0 0.0\n"));
+        assert!(invalid("%%MatrixMarket matrix coordinate real skew-symmetric\n2 2 1\n2 1 1.0\n"));
+        assert!(invalid("%%MatrixMarket matrix coordinate real general\n2 2\n"));
+        assert!(invalid("%%MatrixMarket matrix coordinate real general\n2 2 1\n3 1 1.0\n"));
+        assert!(invalid("%%MatrixMarket matrix coordinate real general\n2 2 1\n0 1 1.0\n"));
+        assert!(invalid("%%MatrixMarket matrix coordinate real general\n2 2 2\n1 1 1.0\n"));
+        assert!(invalid("%%MatrixMarket matrix coordinate real general\n2 2 1\n1 1 1.0\n2 2 1.0\n"));
+        assert!(matches!(
+            read("%%MatrixMarket matrix coordinate real general\n2 2 1\n1 1 x\n"),
+            Err(Error::ParseError { line: 3, .. })
+        ));
+        assert!(matches!(
+            read("%%MatrixMarket matrix coordinate real symmetric\n2 3 0\n"),
+            Err(Error::NotSquare { nrows: 2, ncols: 3 })
+        ));
+        // --- the size line is checked before anything is allocated for the matrix
+        for size in ["0 0 0", "0 3 0", "4294967296 4294967296 0", "2305843009213693952 1 0"] {
+            let result = read(&format!("%%MatrixMarket matrix coordinate real general\n{size}\n"));
+            assert!(matches!(result, Err(Error::InvalidMatrixMarket { line: 2, .. })), "{size}: {result:?}");
+        }
+        assert!(matches!(
+            read("%%MatrixMarket matrix coordinate real general\n% row_labels: a b c\n2 2 0\n"),
+            Err(Error::IncorrectMatrixLabels { expected: 3, actual: 2 })
+        ));
+    }
+}
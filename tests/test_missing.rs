@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod test_missing {
+    use data_matrix::{DataMatrix, DataMatrixBuilder, DataMatrixWriter, Error, Layout};
+
+    #[test]
+    fn gaps_in_three_columns() -> Result<(), Error> {
+        let dm = DataMatrixBuilder::new()
+            .missing_value(-1.0)
+            .from_file("./tests/test_files/three_columns_short.txt")?;
+        assert_eq!(dm.count_missing(), 2);
+        assert_eq!(dm.get_by_label("Alice", "John"), Some(-1.0));
+        assert!(!dm.is_missing(0, 0));
+        assert!(!dm.is_missing(10, 10));
+
+        let dm = DataMatrixBuilder::new()
+            .symmetric(true)
+            .from_file("./tests/test_files/three_columns_short.txt")?;
+        assert_eq!(dm.count_missing(), 5); // the diagonal and (Alice, John)
+        assert_eq!(dm.get_by_label("John", "Alice"), Some(0.0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn na_tokens_in_wide() -> Result<(), Error> {
+        let dm = DataMatrixBuilder::new()
+            .na_tokens(["NA", "", "-"])
+            .missing_value(f64::NAN)
+            .from_wide_file("./tests/test_files/wide_missing.tsv")?;
+        assert_eq!(dm.count_missing(), 3);
+        assert!(dm.is_missing(0, 1) && dm.is_missing(1, 0) && dm.is_missing(1, 2));
+        assert!(dm.get_by_label("G2", "S1").unwrap().is_nan());
+
+        // --- an empty field is not a number unless declared as a NA token
+        let result = DataMatrixBuilder::new().from_wide_file("./tests/test_files/wide_missing.tsv");
+        assert!(matches!(result, Err(Error::ParseError { line: 3, .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn missing_survive_round_trip() -> Result<(), Error> {
+        let dm = DataMatrixBuilder::new()
+            .na_tokens(["NA", "", "-"])
+            .from_wide_file("./tests/test_files/wide_missing.tsv")?;
+        for layout in [Layout::ThreeColumns, Layout::Wide] {
+            let output = std::env::temp_dir().join(format!("datamatrix_test_missing_{}.txt", std::process::id()));
+            DataMatrixWriter::new().layout(layout).to_file(&dm, &output)?;
+            let copy = match layout {
+                Layout::Wide => DataMatrixBuilder::new().from_wide_file(&output)?,
+                _ => DataMatrixBuilder::new().from_file(&output)?,
+            };
+            assert_eq!(copy.count_missing(), 3);
+            let (i, j) = (copy.row_index("G2").unwrap(), copy.col_index("S3").unwrap());
+            assert!(copy.is_missing(i, j));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn fully_missing_row_survives_long_layouts() -> Result<(), Error> {
+        let labels = |names: &[&str]| names.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let mut dm = DataMatrix::from_vec(vec![1.0, 2.0, 3.0, 4.0], labels(&["a", "b"]), labels(&["x", "y"]))?;
+        dm.add_row("c", 0.0)?;
+        dm.add_col("z", 0.0)?;
+        assert_eq!(dm.count_missing(), 5);
+
+        for layout in [Layout::ThreeColumns, Layout::FiveColumns] {
+            let mut buffer = Vec::new();
+            DataMatrixWriter::new().layout(layout).to_writer(&dm, &mut buffer)?;
+            let text = String::from_utf8(buffer).unwrap();
+            assert!(text.contains("c z "), "{text}");
+            let builder = match layout {
+                Layout::FiveColumns => DataMatrixBuilder::new().index_columns(2, 3).data_column(4),
+                _ => DataMatrixBuilder::new(),
+            };
+            let copy = builder.from_str(&text)?;
+            assert_eq!(copy.shape(), (3, 3));
+            assert_eq!(copy.row_labels(), dm.row_labels());
+            assert_eq!(copy.col_labels(), dm.col_labels());
+            assert_eq!(copy.as_slice(), dm.as_slice());
+            assert_eq!(copy.count_missing(), 5);
+            assert!((0..3).all(|j| copy.is_missing(2, j)));
+        }
+
+        Ok(())
+    }
+}
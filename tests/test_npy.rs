@@ -0,0 +1,175 @@
+#[cfg(test)]
+mod test_npy {
+    use data_matrix::{DataMatrix, DataMatrixBuilder, DataMatrixWriter, Error};
+
+    fn labels(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn small() -> Result<DataMatrix, Error> {
+        DataMatrix::from_vec(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], labels(&["a", "b"]), labels(&["x", "y", "z"]))
+    }
+
+    /// A `.npy` file of the given header dictionary and data, in format version 2.0
+    fn npy_v2(dict: &str, data: &[u8]) -> Vec<u8> {
+        let mut bytes = b"\x93NUMPY\x02\x00".to_vec();
+        bytes.extend_from_slice(&(dict.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(dict.as_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    #[test]
+    fn round_trip() -> Result<(), Error> {
+        let dm = small()?;
+        let mut bytes = Vec::new();
+        DataMatrixWriter::new().to_npy_writer(&dm, &mut bytes, false)?;
+        assert_eq!(&bytes[0..8], b"\x93NUMPY\x01\x00");
+        let header_len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+        assert_eq!((10 + header_len) % 64, 0);
+        let header = std::str::from_utf8(&bytes[10..10 + header_len]).unwrap();
+        assert!(header.contains("'fortran_order': False") && header.contains("'shape': (2, 3)"));
+        assert_eq!(bytes.len(), 10 + header_len + 6 * 8);
+
+        // --- labels are not stored
+        let copy = DataMatrixBuilder::new().from_npy_reader(bytes.as_slice())?;
+        assert_eq!(copy.shape(), (2, 3));
+        assert_eq!(copy.as_slice(), dm.as_slice());
+        assert_eq!(copy.row_label(1), "row-2");
+        assert_eq!(copy.col_label(2), "col-3");
+
+        // --- single-precision values in a file
+        let dm32 = DataMatrixBuilder::new().element_type::<f32>().labels(["p", "q"]).from_data(&[0.5, 1.5, 2.5, 3.5])?;
+        let fname = std::env::temp_dir().join(format!("datamatrix_test_npy_{}.npy", std::process::id()));
+        DataMatrixWriter::new().to_npy_file(&dm32, &fname, false)?;
+        let copy = DataMatrixBuilder::new().element_type::<f32>().labels(["p", "q"]).from_npy_file(&fname)?;
+        std::fs::remove_file(&fname)?;
+        assert_eq!(copy.as_slice(), dm32.as_slice());
+        assert_eq!(copy.get_by_label("q", "p"), Some(2.5));
+
+        Ok(())
+    }
+
+    #[test]
+    fn fortran_order() -> Result<(), Error> {
+        let dm = small()?;
+        let mut bytes = Vec::new();
+        DataMatrixWriter::new().to_npy_writer(&dm, &mut bytes, true)?;
+        let values: Vec<f64> = bytes[bytes.len() - 48..].chunks_exact(8).map(|c| f64::from_ne_bytes(c.try_into().unwrap())).collect();
+        assert_eq!(values, vec![1.0, 4.0, 2.0, 5.0, 3.0, 6.0]);
+
+        let copy = DataMatrixBuilder::new().from_npy_reader(bytes.as_slice())?;
+        assert_eq!(copy.shape(), (2, 3));
+        assert_eq!(copy.as_slice(), dm.as_slice());
+
+        Ok(())
+    }
+
+    #[test]
+    fn foreign_files() -> Result<(), Error> {
+        // --- big-endian integers in format version 2.0, with double quotes and no trailing comma
+        let data: Vec<u8> = [1i32, -2, 3, 4].iter().flat_map(|v| v.to_be_bytes()).collect();
+        let bytes = npy_v2("{\"descr\": \">i4\", \"fortran_order\": False, \"shape\": (2, 2)}\n", &data);
+        let dm = DataMatrixBuilder::new().element_type::<i32>().labels(["A", "B"]).from_npy_reader(bytes.as_slice())?;
+        assert_eq!(dm.as_slice(), &[1, -2, 3, 4]);
+        assert_eq!(dm.get_by_label("A", "B"), Some(-2));
+
+        // --- single byte values have no byte order
+        let bytes = npy_v2("{'descr': '|u1', 'fortran_order': True, 'shape': (1, 3), }\n", &[7, 8, 9]);
+        let dm = DataMatrixBuilder::new().element_type::<u8>().from_npy_reader(bytes.as_slice())?;
+        assert_eq!(dm.as_slice(), &[7, 8, 9]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_files() -> Result<(), Error> {
+        let mut bytes = Vec::new();
+        DataMatrixWriter::new().to_npy_writer(&small()?, &mut bytes, false)?;
+
+        let result = DataMatrixBuilder::new().element_type::<f32>().from_npy_reader(bytes.as_slice());
+        assert!(matches!(
+            result,
+            Err(Error::ElementTypeMismatch {
+                expected: "f32",
+                found: "f64"
+            })
+        ));
+        let result = DataMatrixBuilder::new().from_npy_reader(&bytes[..bytes.len() - 1]);
+        assert!(matches!(result, Err(Error::InvalidNpyFormat { .. })));
+        let result = DataMatrixBuilder::new().from_npy_reader(&b"DMATRIX\0\x01\x00"[..]);
+        assert!(matches!(result, Err(Error::InvalidNpyFormat { .. })));
+        // --- labels must match the shape of the array
+        let result = DataMatrixBuilder::new().labels(["a", "b"]).from_npy_reader(bytes.as_slice());
+        assert!(matches!(result, Err(Error::IncorrectMatrixLabels { expected: 2, actual: 3 })));
+
+        let one_dim = npy_v2("{'descr': '<f8', 'fortran_order': False, 'shape': (2,), }\n", &[0; 16]);
+        let result = DataMatrixBuilder::new().from_npy_reader(one_dim.as_slice());
+        assert!(matches!(result, Err(Error::InvalidNpyFormat { .. })));
+        let complex = npy_v2("{'descr': '<c16', 'fortran_order': False, 'shape': (1, 1), }\n", &[0; 16]);
+        let result = DataMatrixBuilder::new().from_npy_reader(complex.as_slice());
+        assert!(matches!(result, Err(Error::InvalidNpyFormat { .. })));
+        let oversized = npy_v2("{'descr': '<f8', 'fortran_order': False, 'shape': (4294967296, 536870912), }\n", &[0; 16]);
+        let result = DataMatrixBuilder::new().from_npy_reader(oversized.as_slice());
+        assert!(matches!(result, Err(Error::InvalidNpyFormat { .. })));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "npz")]
+    #[test]
+    fn npz_archive() -> Result<(), Error> {
+        use std::io::Cursor;
+
+        let dm = DataMatrixBuilder::new()
+            .skip_header(true)
+            .index_columns(3, 4)
+            .symmetric(true)
+            .from_file("./tests/test_files/cities_by_distance.csv.gz")?;
+        let mut buffer = Cursor::new(Vec::new());
+        DataMatrixWriter::new().to_npz_writer(&dm, &mut buffer, true)?;
+        buffer.set_position(0);
+        let copy = DataMatrixBuilder::new().from_npz_reader(buffer)?;
+        assert_eq!(copy.row_labels(), dm.row_labels());
+        assert_eq!(copy.col_labels(), dm.col_labels());
+        assert_eq!(copy.as_slice(), dm.as_slice());
+
+        // --- non-ASCII labels in a file
+        let dm = DataMatrix::from_vec(vec![1.0, 2.0], labels(&["Kraków"]), labels(&["Zürich", "Łódź"]))?;
+        let fname = std::env::temp_dir().join(format!("datamatrix_test_npy_{}.npz", std::process::id()));
+        DataMatrixWriter::new().to_npz_file(&dm, &fname, false)?;
+        let copy = DataMatrixBuilder::new().from_npz_file(&fname)?;
+        std::fs::remove_file(&fname)?;
+        assert_eq!(copy.get_by_label("Kraków", "Łódź"), Some(2.0));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "npz")]
+    #[test]
+    fn npz_without_labels() -> Result<(), Error> {
+        use std::io::{Cursor, Write};
+        use zip::write::SimpleFileOptions;
+
+        let mut values = Vec::new();
+        DataMatrixWriter::new().to_npy_writer(&small()?, &mut values, false)?;
+        // --- as written by numpy.savez_compressed(fname, values=...)
+        let mut archive = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        archive.start_file("values.npy", SimpleFileOptions::default()).unwrap();
+        archive.write_all(&values)?;
+        let bytes = archive.finish().unwrap().into_inner();
+
+        let dm = DataMatrixBuilder::new().from_npz_reader(Cursor::new(&bytes))?;
+        assert_eq!(dm.as_slice(), small()?.as_slice());
+        assert_eq!(dm.row_labels(), &["row-1", "row-2"]);
+
+        let mut archive = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        archive.start_file("other.npy", SimpleFileOptions::default()).unwrap();
+        archive.write_all(&values)?;
+        let bytes = archive.finish().unwrap().into_inner();
+        let result = DataMatrixBuilder::new().from_npz_reader(Cursor::new(&bytes));
+        assert!(matches!(result, Err(Error::InvalidNpyFormat { .. })));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod test_packed {
+    use data_matrix::{DataMatrixBuilder, DuplicatePolicy, Error, PackedDataMatrix};
+
+    const CITIES: &str = "./tests/test_files/cities_by_distance.csv.gz";
+
+    fn cities() -> DataMatrixBuilder {
+        DataMatrixBuilder::new().skip_header(true).index_columns(3, 4)
+    }
+
+    #[test]
+    fn same_entries_as_dense() -> Result<(), Error> {
+        let dense = cities().symmetric(true).from_file(CITIES)?;
+        for with_diagonal in [true, false] {
+            let packed = cities().store_diagonal(with_diagonal).from_file_packed(CITIES)?;
+            assert_eq!(packed.has_diagonal(), with_diagonal);
+            assert_eq!(packed.row_labels(), dense.row_labels());
+            assert_eq!(packed.as_slice().len(), if with_diagonal { 120 } else { 105 });
+            for (i, j, value) in packed.iter() {
+                assert_eq!(Some(value), dense.get_ref(i, j));
+            }
+            assert_eq!(packed.iter().count(), 15 * 15);
+            assert_eq!(packed.row(3).copied().collect::<Vec<_>>(), dense.row(3));
+            assert_eq!(packed.to_dense().as_slice(), dense.as_slice());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn dense_conversion() -> Result<(), Error> {
+        let dense = DataMatrixBuilder::new()
+            .symmetric(true)
+            .from_str("A B 1.0\nB C 2.0\nA A 0.5\n")?;
+        let packed = PackedDataMatrix::from_dense(&dense, true)?;
+        assert_eq!(packed.as_slice(), &[0.5, 1.0, 0.0, 0.0, 2.0, 0.0]);
+        assert_eq!(packed.count_missing(), 3);
+        assert!(packed.is_missing(2, 0) && !packed.is_missing(0, 0));
+        let copy = packed.to_dense();
+        assert_eq!(copy.as_slice(), dense.as_slice());
+        assert_eq!(copy.count_missing(), dense.count_missing());
+
+        let packed = PackedDataMatrix::from_dense(&dense, false)?;
+        assert_eq!(packed.as_slice(), &[1.0, 0.0, 2.0]);
+        assert_eq!(packed.get(0, 0), Some(0.0));
+        assert_eq!(packed.get(0, 3), None);
+
+        let asymmetric = DataMatrixBuilder::new().from_str("A B 1.0\nB A 2.0\n")?;
+        let result = PackedDataMatrix::from_dense(&asymmetric, true);
+        assert!(matches!(result, Err(Error::LabelMismatch { index: 0, .. })));
+        let asymmetric = DataMatrixBuilder::new().from_str("A A 0.0\nA B 1.0\nB A 2.0\n")?;
+        let result = PackedDataMatrix::from_dense(&asymmetric, true);
+        assert!(matches!(result, Err(Error::NotSymmetric { .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn duplicates_and_indices() -> Result<(), Error> {
+        let packed = DataMatrixBuilder::new()
+            .duplicates(DuplicatePolicy::Average)
+            .from_reader_packed("A B 1.0\nB A 2.0\nB C 4.0\n".as_bytes())?;
+        assert_eq!(packed.get_by_label("A", "B"), Some(1.5));
+
+        // --- explicit indices reorder rows and columns
+        let packed = DataMatrixBuilder::new()
+            .index_columns(2, 3)
+            .data_column(4)
+            .from_reader_packed("A B 2 0 1.0\nB C 0 1 4.0\n".as_bytes())?;
+        assert_eq!(packed.row_labels(), &["B", "C", "A"]);
+        assert_eq!(packed.get(2, 0), Some(1.0));
+        assert_eq!(packed.get(0, 1), Some(4.0));
+
+        let result = PackedDataMatrix::from_vec(vec![1.0; 4], vec!["A".into(), "B".into()], true);
+        assert!(matches!(result, Err(Error::IncorrectDataLength { expected: 3, n_data: 4, .. })));
+
+        Ok(())
+    }
+}
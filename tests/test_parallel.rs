@@ -0,0 +1,78 @@
+#[cfg(all(test, feature = "rayon"))]
+mod test_parallel {
+    use data_matrix::{DataMatrix, DataMatrixBuilder, DuplicatePolicy, Error};
+    use std::fmt::Write as _;
+
+    /// Loads a file both sequentially and in parallel; results must be the same.
+    fn load_both(builder: DataMatrixBuilder, fname: &str) -> Result<(DataMatrix, DataMatrix), Error> {
+        let sequential = builder.clone().from_file(fname)?;
+        let parallel = builder.clone().parallel(true).from_file(fname)?;
+        assert_eq!(sequential.row_labels(), parallel.row_labels());
+        assert_eq!(sequential.col_labels(), parallel.col_labels());
+        assert_eq!(sequential.to_rows(), parallel.to_rows());
+        for i in 0..sequential.nrows() {
+            for j in 0..sequential.ncols() {
+                assert_eq!(sequential.is_missing(i, j), parallel.is_missing(i, j));
+            }
+        }
+        let sparse = builder.parallel(true).from_file_sparse(fname)?;
+        assert_eq!(sparse.to_dense().to_rows(), sequential.to_rows());
+        Ok((sequential, parallel))
+    }
+
+    #[test]
+    fn same_as_sequential() -> Result<(), Error> {
+        let builder = DataMatrixBuilder::new().skip_header(true).index_columns(3, 4).symmetric(true);
+        load_both(builder, "./tests/test_files/cities_by_distance.csv.gz")?;
+        let builder = DataMatrixBuilder::new().skip_header(true).symmetric(true);
+        load_both(builder, "./tests/test_files/cities_by_distance.csv.gz")?;
+        let builder = DataMatrixBuilder::new().symmetric(true).duplicates(DuplicatePolicy::Average);
+        load_both(builder, "./tests/test_files/three_columns_duplicated.txt")?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn large_asymmetric_input() -> Result<(), Error> {
+        // --- about 300 000 lines (more than one block), with labels appearing in a shuffled order and a few NA entries
+        let mut text = String::from("# a comment\nrow col value\n");
+        for k in 0..300_000usize {
+            let (i, j) = ((k * 7919) % 1000, (k * 104729) % 1009);
+            if k % 997 == 0 {
+                writeln!(text, "r{i} c{j} NA").unwrap();
+            } else {
+                writeln!(text, "r{i} c{j} {k}").unwrap();
+            }
+        }
+        let fname = std::env::temp_dir().join(format!("datamatrix_test_parallel_{}.txt", std::process::id()));
+        std::fs::write(&fname, text)?;
+        let builder = DataMatrixBuilder::new().skip_header(true).missing_value(-1.0);
+        let (dm, _) = load_both(builder, fname.to_str().unwrap())?;
+        assert_eq!((dm.nrows(), dm.ncols()), (1000, 1009));
+        std::fs::remove_file(&fname)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn same_errors() {
+        let fname = std::env::temp_dir().join(format!("datamatrix_test_parallel_errors_{}.txt", std::process::id()));
+        for text in [
+            "A B 0 1 1.0\n# comment\nB C 1 2 2.0\nC A 2 two 3.0\n",
+            "A B 0 1 1.0\nB C 1 2 2.0\nC A 2 1 3.0\n",
+            "A B 0 1 1.0\nB C 1 2 2.0\nB A 1 0 3.0\n",
+            "A B 0 1 1.0\n\nB\n",
+        ] {
+            std::fs::write(&fname, text).unwrap();
+            let builder = DataMatrixBuilder::new()
+                .index_columns(2, 3)
+                .data_column(4)
+                .symmetric(true)
+                .duplicates(DuplicatePolicy::Error);
+            let sequential = builder.clone().from_file(&fname).unwrap_err();
+            let parallel = builder.parallel(true).from_file(&fname).unwrap_err();
+            assert_eq!(sequential.to_string(), parallel.to_string());
+        }
+        std::fs::remove_file(&fname).unwrap();
+    }
+}
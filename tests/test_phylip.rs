@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod test_phylip {
+    use data_matrix::{DataMatrixBuilder, DataMatrixWriter, Error, Layout};
+
+    #[test]
+    fn lower_triangle_wrapped() -> Result<(), Error> {
+        let dm = DataMatrixBuilder::new().from_phylip("./tests/test_files/lower_triangle.phy")?;
+        assert_eq!(dm.row_labels(), ["Human", "Chimp", "Gorilla", "Orangutan"]);
+        assert_eq!(dm.get_by_label("Human", "Human"), Some(0.0));
+        assert_eq!(dm.get_by_label("Chimp", "Orangutan"), Some(0.1523));
+        assert_eq!(dm.get_by_label("Orangutan", "Gorilla"), Some(0.1514));
+
+        Ok(())
+    }
+
+    #[test]
+    fn strict_names() -> Result<(), Error> {
+        let dm = DataMatrixBuilder::new()
+            .strict_phylip_names(true)
+            .from_phylip("./tests/test_files/square_strict.phy")?;
+        assert_eq!(dm.row_labels(), ["Homo sap", "Pan trog", "Gorilla g"]);
+        assert_eq!(dm.get_by_label("Pan trog", "Gorilla g"), Some(2.2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip() -> Result<(), Error> {
+        let dm = DataMatrixBuilder::new().from_phylip("./tests/test_files/lower_triangle.phy")?;
+        for strict in [false, true] {
+            for lower_triangle in [false, true] {
+                let output = std::env::temp_dir().join(format!("datamatrix_test_round_trip_{}.phy", std::process::id()));
+                DataMatrixWriter::new()
+                    .layout(Layout::Phylip { strict, lower_triangle })
+                    .to_file(&dm, &output)?;
+                let copy = DataMatrixBuilder::new()
+                    .strict_phylip_names(strict)
+                    .from_phylip(&output)?;
+                assert_eq!(dm.row_labels(), copy.row_labels());
+                assert_eq!(dm.to_rows(), copy.to_rows());
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn asymmetric_square() {
+        let input = std::env::temp_dir().join(format!("datamatrix_test_asymmetric_{}.phy", std::process::id()));
+        std::fs::write(&input, "2\nA 0 1\nB 2 0\n").unwrap();
+        let result = DataMatrixBuilder::new().from_phylip(&input);
+        assert!(matches!(result, Err(Error::NotSymmetric { value, transposed, .. }) if value == "2" && transposed == "1"));
+    }
+
+    #[test]
+    fn strict_name_too_long() -> Result<(), Error> {
+        let dm = DataMatrixBuilder::new()
+            .labels(["a_very_long_name", "short"])
+            .from_data(&[0.0, 1.0, 1.0, 0.0])?;
+        let result = DataMatrixWriter::new()
+            .layout(Layout::Phylip { strict: true, lower_triangle: false })
+            .to_writer(&dm, std::io::sink());
+        assert!(matches!(result, Err(Error::LabelTooLong { max_length: 10, .. })));
+
+        Ok(())
+    }
+}
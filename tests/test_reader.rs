@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod test_reader {
+    use data_matrix::{Compression, DataMatrixBuilder, Error};
+    use std::io::Cursor;
+
+    #[test]
+    fn from_str_three_columns() -> Result<(), Error> {
+        let text = std::fs::read_to_string("./tests/test_files/three_columns_short.txt")?;
+        let from_text = DataMatrixBuilder::new().symmetric(true).from_str(&text)?;
+        let from_file = DataMatrixBuilder::new()
+            .symmetric(true)
+            .from_file("./tests/test_files/three_columns_short.txt")?;
+        assert_eq!(from_text.row_labels(), from_file.row_labels());
+        assert_eq!(from_text.to_rows(), from_file.to_rows());
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_gzipped_buffer() -> Result<(), Error> {
+        let bytes = std::fs::read("./tests/test_files/cities_by_distance.csv.gz")?;
+        let dm = DataMatrixBuilder::new()
+            .separator(',')
+            .compression(Compression::Gzip)
+            .skip_header(true)
+            .index_columns(3, 4)
+            .symmetric(true)
+            .from_reader(Cursor::new(bytes))?;
+        let err = dm.get_by_label("Tokyo", "New York City").unwrap() - 10851.73;
+        assert!(err.abs() < 0.0001);
+
+        Ok(())
+    }
+
+    #[test]
+    fn single_column_from_str() -> Result<(), Error> {
+        let dm = DataMatrixBuilder::new()
+            .labels(["A", "B"])
+            .data_column(0)
+            .from_str("1.1\n2.2\n3.3\n4.4\n")?;
+        assert_eq!(dm.get_by_label("B", "A"), Some(3.3));
+
+        Ok(())
+    }
+
+    #[test]
+    fn wide_and_phylip_readers() -> Result<(), Error> {
+        let dm = DataMatrixBuilder::new()
+            .separator(',')
+            .from_wide_reader(Cursor::new(",S1,S2\nG1,1,2\nG2,3,4\n"))?;
+        assert_eq!(dm.get_by_label("G2", "S1"), Some(3.0));
+
+        let dm = DataMatrixBuilder::new().from_phylip_reader(Cursor::new("2\nA 0 1.5\nB 1.5 0\n"))?;
+        assert_eq!(dm.get_by_label("B", "A"), Some(1.5));
+
+        Ok(())
+    }
+
+    #[test]
+    fn compression_overrides_extension() {
+        // --- plain text can't be read as gzip, regardless of the file name
+        let result = DataMatrixBuilder::new()
+            .compression(Compression::Gzip)
+            .from_file("./tests/test_files/three_columns_short.txt");
+        assert!(matches!(result, Err(Error::IoError(_))));
+    }
+}
@@ -0,0 +1,77 @@
+#[cfg(test)]
+mod test_reordering {
+    use data_matrix::{Alignment, DataMatrix, DataMatrixBuilder, Error};
+
+    fn labels(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn permute_and_sort() -> Result<(), Error> {
+        let mut dm = DataMatrix::from_vec((0..6).collect(), labels(&["r2", "r1"]), labels(&["c3", "c1", "c2"]))?;
+        dm.permute(&[1, 0], &[2, 0, 1])?;
+        assert_eq!(dm.row_labels(), ["r1", "r2"]);
+        assert_eq!(dm.col_labels(), ["c2", "c3", "c1"]);
+        assert_eq!(dm.as_slice(), &[5, 3, 4, 2, 0, 1]);
+        assert_eq!(dm.get_by_label("r2", "c1"), Some(1));
+
+        assert!(matches!(dm.permute(&[0], &[0, 1, 2]), Err(Error::InvalidPermutation { expected: 2, actual: 1 })));
+        assert!(matches!(dm.permute(&[0, 0], &[0, 1, 2]), Err(Error::DuplicateLabel { .. })));
+        assert!(matches!(dm.permute(&[0, 1], &[0, 1, 3]), Err(Error::IndexOutOfBounds { .. })));
+        assert_eq!(dm.col_labels(), ["c2", "c3", "c1"]);
+
+        dm.sort_labels();
+        assert_eq!(dm.col_labels(), ["c1", "c2", "c3"]);
+        assert_eq!(dm.as_slice(), &[4, 5, 3, 1, 2, 0]);
+        assert_eq!(dm.col_index("c3"), Some(2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn reorder_like_other() -> Result<(), Error> {
+        let a = DataMatrixBuilder::new().from_str("x p 1.0\nx q 2.0\ny p 3.0\n")?;
+        let mut b = DataMatrixBuilder::new().from_str("y q 4.0\ny p 5.0\nx q 6.0\n")?;
+        assert_eq!(b.row_labels(), ["y", "x"]);
+        b.reorder_like(&a)?;
+        assert_eq!(b.row_labels(), a.row_labels());
+        assert_eq!(b.col_labels(), a.col_labels());
+        assert_eq!(b.as_slice(), &[0.0, 6.0, 5.0, 4.0]);
+        assert!(b.is_missing(0, 0) && !a.is_missing(0, 0));
+
+        let c = DataMatrixBuilder::new().from_str("x p 1.0\nz q 2.0\n")?;
+        assert!(matches!(b.reorder_like(&c), Err(Error::UnknownLabel { label }) if label == "z"));
+        let d = DataMatrixBuilder::new().from_str("x p 1.0\nx q 2.0\n")?;
+        assert!(matches!(b.reorder_like(&d), Err(Error::InvalidPermutation { expected: 2, actual: 1 })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn align_matrices() -> Result<(), Error> {
+        let a = DataMatrixBuilder::new().symmetric(true).from_str("a b 1\na c 2\nb c 3\n")?;
+        let b = DataMatrixBuilder::new().symmetric(true).from_str("d c 7\nc b 8\n")?;
+
+        let (a1, b1) = DataMatrix::align(&a, &b, Alignment::Intersection)?;
+        assert_eq!(a1.row_labels(), ["b", "c"]);
+        assert_eq!(b1.row_labels(), a1.row_labels());
+        assert_eq!(b1.col_labels(), a1.col_labels());
+        assert_eq!(a1.as_slice(), &[0.0, 3.0, 3.0, 0.0]);
+        assert_eq!(b1.as_slice(), &[0.0, 8.0, 8.0, 0.0]);
+
+        let (a2, b2) = DataMatrix::align(&a, &b, Alignment::Union { fill: -1.0 })?;
+        assert_eq!(a2.row_labels(), ["a", "b", "c", "d"]);
+        assert_eq!(b2.col_labels(), ["a", "b", "c", "d"]);
+        assert_eq!(a2.row(3), &[-1.0; 4]);
+        assert_eq!(b2.get_by_label("d", "c"), Some(7.0));
+        assert_eq!(b2.get_by_label("a", "b"), Some(-1.0));
+        // --- 7 entries of `a` and the diagonal are missing
+        assert_eq!(a2.count_missing(), 7 + 3);
+        assert!(a2.is_missing(3, 2) && !b2.is_missing(3, 2));
+
+        let c = DataMatrixBuilder::new().from_str("x y 1\n")?;
+        assert!(matches!(DataMatrix::align(&a, &c, Alignment::Intersection), Err(Error::EmptySelection { nrows: 0, ncols: 0 })));
+
+        Ok(())
+    }
+}
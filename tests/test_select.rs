@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod test_select {
+    use data_matrix::{DataMatrix, DataMatrixBuilder, Error};
+
+    fn labels(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn grid() -> Result<DataMatrix<i32>, Error> {
+        DataMatrix::from_vec((0..12).collect(), labels(&["r1", "r2", "r3"]), labels(&["c1", "c2", "c3", "c4"]))
+    }
+
+    #[test]
+    fn select_by_labels_and_indices() -> Result<(), Error> {
+        let dm = grid()?;
+        let selected = dm.select(&["r3", "r1"], &["c4", "c2"])?;
+        assert_eq!(selected.row_labels(), ["r3", "r1"]);
+        assert_eq!(selected.col_labels(), ["c4", "c2"]);
+        assert_eq!(selected.as_slice(), &[11, 9, 3, 1]);
+        assert_eq!(selected.get_by_label("r1", "c4"), Some(3));
+
+        let by_index = dm.select_indices(&[2, 0], &[3, 1])?;
+        assert_eq!(by_index.as_slice(), selected.as_slice());
+        assert_eq!(by_index.row_labels(), selected.row_labels());
+
+        assert!(matches!(dm.select(&["r1", "r9"], &["c1"]), Err(Error::UnknownLabel { label }) if label == "r9"));
+        assert!(matches!(dm.select(&["r1"], &["c1", "c1"]), Err(Error::DuplicateLabel { label }) if label == "c1"));
+        assert!(matches!(dm.select_indices(&[0], &[4]), Err(Error::IndexOutOfBounds { row: 0, col: 4, .. })));
+        assert!(matches!(dm.select(&[], &["c1"]), Err(Error::EmptySelection { nrows: 0, ncols: 1 })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn slices_and_filters() -> Result<(), Error> {
+        let dm = grid()?;
+        let sliced = dm.slice(1.., 1..=2)?;
+        assert_eq!(sliced.shape(), (2, 2));
+        assert_eq!(sliced.as_slice(), &[5, 6, 9, 10]);
+        assert_eq!(sliced.col_labels(), ["c2", "c3"]);
+        assert_eq!(dm.slice(.., ..)?.as_slice(), dm.as_slice());
+        assert!(matches!(dm.slice(2..4, ..), Err(Error::IndexOutOfBounds { row: 3, .. })));
+        assert!(matches!(dm.slice(1..1, ..), Err(Error::EmptySelection { .. })));
+        assert!(matches!(dm.slice(..=usize::MAX, ..), Err(Error::IndexOutOfBounds { row: 3, col: 0, .. })));
+        assert!(matches!(dm.slice(.., 5..=usize::MAX), Err(Error::IndexOutOfBounds { row: 0, col: 5, .. })));
+
+        let rows = dm.filter_rows(|label| label != "r2")?;
+        assert_eq!(rows.row_labels(), ["r1", "r3"]);
+        assert_eq!(rows.row(1), &[8, 9, 10, 11]);
+        let cols = dm.filter_cols(|label| label.ends_with('1') || label.ends_with('4'))?;
+        assert_eq!(cols.as_slice(), &[0, 3, 4, 7, 8, 11]);
+        assert!(matches!(dm.filter_cols(|label| label.starts_with('x')), Err(Error::EmptySelection { ncols: 0, .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn select_taxa() -> Result<(), Error> {
+        let dm = DataMatrixBuilder::new()
+            .symmetric(true)
+            .from_str("a b 1.0\na c 2.0\nb c 3.0\na d 4.0\n")?;
+        let selected = dm.select_symmetric(&["c", "a"])?;
+        assert_eq!(selected.row_labels(), selected.col_labels());
+        assert_eq!(selected.as_slice(), &[0.0, 2.0, 2.0, 0.0]);
+        // --- missing flags follow the selected entries
+        assert_eq!(selected.count_missing(), 2);
+        assert!(selected.is_missing(0, 0) && !selected.is_missing(0, 1));
+        let complete = dm.select(&["a"], &["b", "c", "d"])?;
+        assert_eq!(complete.count_missing(), 0);
+        assert!(matches!(dm.select_symmetric(&["a", "e"]), Err(Error::UnknownLabel { .. })));
+
+        Ok(())
+    }
+}
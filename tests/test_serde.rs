@@ -0,0 +1,146 @@
+#[cfg(all(test, feature = "serde"))]
+mod test_serde {
+    use data_matrix::{DataMatrix, DataMatrixBuilder, DuplicatePolicy, Error};
+    use serde_json::error::Category;
+
+    fn labels(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    /// Deserializes a matrix which is expected to be rejected; returns the message of the error
+    fn rejected(json: &str) -> String {
+        let error = serde_json::from_str::<DataMatrix>(json).unwrap_err();
+        assert_eq!(error.classify(), Category::Data, "{error}");
+        error.to_string()
+    }
+
+    #[test]
+    fn dense_matrix() -> Result<(), Error> {
+        let dm = DataMatrix::from_vec(vec![1.5, 2.5, 0.0, -1.0], labels(&["a", "b"]), labels(&["x", "y"]))?;
+        let json = serde_json::to_string(&dm).unwrap();
+        assert_eq!(json, r#"{"row_labels":["a","b"],"col_labels":["x","y"],"values":[1.5,2.5,0.0,-1.0]}"#);
+
+        let copy: DataMatrix = serde_json::from_str(&json).unwrap();
+        assert_eq!(copy.row_labels(), dm.row_labels());
+        assert_eq!(copy.col_labels(), dm.col_labels());
+        assert_eq!(copy.as_slice(), dm.as_slice());
+        assert_eq!(copy.count_missing(), 0);
+
+        // --- integer values
+        let dm = DataMatrix::from_vec(vec![1u32, 2], labels(&["r"]), labels(&["x", "y"]))?;
+        let json = serde_json::to_string(&dm).unwrap();
+        assert_eq!(json, r#"{"row_labels":["r"],"col_labels":["x","y"],"values":[1,2]}"#);
+        let copy: DataMatrix<u32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(copy.as_slice(), &[1, 2]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn symmetric_matrix() -> Result<(), Error> {
+        let dm = DataMatrixBuilder::new().symmetric(true).from_str("A B 1\nA C 2\nB C 3\nA A 0\nB B 0\nC C 4\n")?;
+        let json = serde_json::to_string(&dm).unwrap();
+        assert_eq!(json, r#"{"labels":["A","B","C"],"upper_triangle":[0.0,1.0,2.0,0.0,3.0,4.0]}"#);
+
+        let copy: DataMatrix = serde_json::from_str(&json).unwrap();
+        assert_eq!(copy.row_labels(), copy.col_labels());
+        assert_eq!(copy.as_slice(), dm.as_slice());
+        assert_eq!(copy.count_missing(), 0);
+
+        // --- a square matrix which is not symmetric takes the full form
+        let dm = DataMatrix::from_vec(vec![0.0, 1.0, 2.0, 0.0], labels(&["A", "B"]), labels(&["A", "B"]))?;
+        let json = serde_json::to_string(&dm).unwrap();
+        assert_eq!(json, r#"{"row_labels":["A","B"],"col_labels":["A","B"],"values":[0.0,1.0,2.0,0.0]}"#);
+
+        Ok(())
+    }
+
+    #[test]
+    fn missing_entries() -> Result<(), Error> {
+        let dm = DataMatrixBuilder::new().from_str("a x 1.5\na y 2.5\nb z -1\n")?;
+        let json = serde_json::to_string(&dm).unwrap();
+        assert_eq!(
+            json,
+            r#"{"row_labels":["a","b"],"col_labels":["x","y","z"],"values":[1.5,2.5,0.0,0.0,0.0,-1.0],"missing":[2,3,4]}"#
+        );
+        let copy: DataMatrix = serde_json::from_str(&json).unwrap();
+        assert_eq!(copy.as_slice(), dm.as_slice());
+        assert_eq!(copy.count_missing(), 3);
+        assert!(copy.is_missing(1, 0) && !copy.is_missing(1, 2));
+
+        // --- positions in the upper triangle: (A, A) and (B, B)
+        let dm = DataMatrixBuilder::new().symmetric(true).from_str("A B 1\nA C 2\nB C 3\nC C 4\n")?;
+        let json = serde_json::to_string(&dm).unwrap();
+        assert_eq!(json, r#"{"labels":["A","B","C"],"upper_triangle":[0.0,1.0,2.0,0.0,3.0,4.0],"missing":[0,3]}"#);
+        let copy: DataMatrix = serde_json::from_str(&json).unwrap();
+        assert_eq!(copy.as_slice(), dm.as_slice());
+        assert_eq!(copy.count_missing(), 2);
+        assert!(copy.is_missing(1, 1) && !copy.is_missing(2, 2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_matrix() {
+        let full = r#"{"row_labels":["a","b"],"col_labels":["x"],"values":[1.0,2.0]}"#;
+        assert!(serde_json::from_str::<DataMatrix>(full).is_ok());
+
+        assert_eq!(
+            rejected(r#"{"row_labels":["a","b"],"col_labels":["x"],"values":[1.0]}"#),
+            "Incorrect number of data values: 1; expected 2 values for a 2 x 1 matrix."
+        );
+        assert_eq!(
+            rejected(r#"{"row_labels":["a","b"],"col_labels":[],"values":[1.0,2.0]}"#),
+            "Incorrect number of data values: 2; expected 0 values for a 2 x 0 matrix."
+        );
+        assert_eq!(
+            rejected(r#"{"row_labels":["a","b"],"col_labels":["x"],"values":[1.0,2.0],"missing":[2]}"#),
+            "missing entry 2 out of bounds of 2 values"
+        );
+        assert_eq!(
+            rejected(r#"{"row_labels":["a","b"],"values":[1.0,2.0]}"#),
+            "expected either `labels` and `upper_triangle`, or `row_labels`, `col_labels` and `values`"
+        );
+        assert_eq!(
+            rejected(r#"{"row_labels":["a","b"],"col_labels":["x"],"values":[1.0,2.0],"shape":2}"#),
+            "unknown field `shape`, expected one of `labels`, `upper_triangle`, `row_labels`, `col_labels`, `values`, `missing` at line 1 column 69"
+        );
+
+        assert_eq!(
+            rejected(r#"{"labels":["a","b"],"upper_triangle":[1.0,2.0]}"#),
+            "invalid length 2, expected the upper triangle of a square matrix of the given labels"
+        );
+        let copy: DataMatrix = serde_json::from_str(r#"{"labels":["a","b"],"upper_triangle":[1.0,2.0,3.0]}"#).unwrap();
+        assert_eq!(copy.as_slice(), &[1.0, 2.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn builder_settings() -> Result<(), Error> {
+        let builder = DataMatrixBuilder::new()
+            .label_columns(2, 1)
+            .data_column(3)
+            .separator(',')
+            .symmetric(true)
+            .skip_header(true)
+            .duplicates(DuplicatePolicy::Average);
+        let json = serde_json::to_value(&builder).unwrap();
+        assert_eq!(json["separator"], ",");
+        assert_eq!(json["duplicates"], "average");
+
+        let restored: DataMatrixBuilder = serde_json::from_value(json).unwrap();
+        let input = "id,second,first,value\n1,B,A,2.0\n2,C,A,4.0\n3,C,A,6.0\n";
+        let expected = builder.from_str(input)?;
+        let dm = restored.from_str(input)?;
+        assert_eq!(dm.row_labels(), expected.row_labels());
+        assert_eq!(dm.as_slice(), expected.as_slice());
+        assert_eq!(dm.get_by_label("C", "A"), Some(5.0));
+
+        // --- settings which are not given keep their defaults, as in a short configuration file
+        let builder: DataMatrixBuilder<i32> = serde_json::from_str(r#"{"symmetric":true,"missing_value":-1}"#).unwrap();
+        let dm = builder.from_str("A B 5\n")?;
+        assert_eq!(dm.get_by_label("B", "A"), Some(5));
+        assert_eq!(dm.get_by_label("A", "A"), Some(-1));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod test_sparse {
+    use data_matrix::{DataMatrix, DataMatrixBuilder, DuplicatePolicy, Error, SparseDataMatrix};
+
+    /// Checks that a sparse matrix holds the same entries as a dense one
+    fn assert_same(sparse: &SparseDataMatrix, dense: &DataMatrix) {
+        assert_eq!(sparse.row_labels(), dense.row_labels());
+        assert_eq!(sparse.col_labels(), dense.col_labels());
+        assert_eq!(sparse.count_missing(), dense.count_missing());
+        for i in 0..dense.nrows() {
+            for j in 0..dense.ncols() {
+                assert_eq!(sparse.get(i, j), dense.get(i, j));
+                assert_eq!(sparse.is_missing(i, j), dense.is_missing(i, j));
+            }
+        }
+        assert_eq!(sparse.to_dense().as_slice(), dense.as_slice());
+    }
+
+    #[test]
+    fn same_entries_as_dense() -> Result<(), Error> {
+        let fname = "./tests/test_files/cities_by_distance.csv.gz";
+        let builder = DataMatrixBuilder::new().skip_header(true).index_columns(3, 4).symmetric(true);
+        assert_same(&builder.clone().from_file_sparse(fname)?, &builder.from_file(fname)?);
+        let builder = DataMatrixBuilder::new().missing_value(-1.0);
+        let fname = "./tests/test_files/three_columns_short.txt";
+        assert_same(&builder.clone().from_file_sparse(fname)?, &builder.from_file(fname)?);
+        let builder = DataMatrixBuilder::new().symmetric(true).duplicates(DuplicatePolicy::Average);
+        let fname = "./tests/test_files/three_columns_duplicated.txt";
+        assert_same(&builder.clone().from_file_sparse(fname)?, &builder.from_file(fname)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn stored_entries() -> Result<(), Error> {
+        let sparse = DataMatrixBuilder::new().from_reader_sparse("b y 2\na x 1\nb x 3\na y NA\n".as_bytes())?;
+        assert_eq!(sparse.nnz(), 3);
+        let entries: Vec<_> = sparse.iter().map(|(i, j, &v)| (i, j, v)).collect();
+        assert_eq!(entries, vec![(0, 0, 2.0), (0, 1, 3.0), (1, 1, 1.0)]);
+        assert_eq!(sparse.row(1).collect::<Vec<_>>(), vec![(1, &1.0)]);
+        assert_eq!(sparse.get_by_label("a", "y"), Some(0.0));
+        assert!(sparse.is_missing(1, 0));
+        assert_eq!(sparse.get(2, 0), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn explicit_zeros_in_both_layouts() -> Result<(), Error> {
+        let long = DataMatrixBuilder::new().from_reader_sparse("a a 0\na b 1\nb a 0\nb b NA\n".as_bytes())?;
+        let single = DataMatrixBuilder::new()
+            .labels(["a", "b"])
+            .data_column(0)
+            .from_reader_sparse("0\n1\n0\nNA\n".as_bytes())?;
+        assert_eq!(long.nnz(), 3);
+        assert_eq!(single.nnz(), long.nnz());
+        assert_eq!(single.iter().collect::<Vec<_>>(), long.iter().collect::<Vec<_>>());
+        assert!(!single.is_missing(0, 0) && single.is_missing(1, 1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn dense_conversion() -> Result<(), Error> {
+        let labels: Vec<String> = vec!["A".into(), "B".into(), "C".into()];
+        let dense = DataMatrix::from_vec(vec![0, 1, 0, 0, 0, 2, 0, 0, 0], labels.clone(), labels)?;
+        let sparse = SparseDataMatrix::from_dense(&dense, 0);
+        assert_eq!(sparse.nnz(), 2);
+        assert_eq!(sparse.get_by_label("B", "C"), Some(2));
+        let copy = sparse.to_dense();
+        assert_eq!(copy.as_slice(), dense.as_slice());
+        assert_eq!(copy.count_missing(), 7);
+
+        let result = DataMatrixBuilder::new().from_reader_sparse("# nothing here\n".as_bytes());
+        assert!(matches!(result, Err(Error::IncorrectMatrixLabels { .. })));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod test_storage {
+    use data_matrix::{DataMatrix, DataMatrixBuilder, Error};
+
+    fn labels(prefix: &str, n: usize) -> Vec<String> {
+        (0..n).map(|i| format!("{prefix}{i}")).collect()
+    }
+
+    #[test]
+    fn row_major_buffer() -> Result<(), Error> {
+        let dm = DataMatrix::new(vec![vec![1, 2, 3], vec![4, 5, 6]], labels("r", 2), labels("c", 3))?;
+        assert_eq!(dm.shape(), (2, 3));
+        assert_eq!(dm.as_slice(), &[1, 2, 3, 4, 5, 6]);
+        assert_eq!(dm.row(1), &[4, 5, 6]);
+        assert_eq!(dm.rows().collect::<Vec<_>>(), vec![&[1, 2, 3][..], &[4, 5, 6][..]]);
+        assert_eq!(dm.get(1, 2), Some(6));
+        assert_eq!(dm.get(0, 3), None);
+        assert_eq!(dm.to_rows(), vec![vec![1, 2, 3], vec![4, 5, 6]]);
+        assert_eq!(dm.into_vec(), vec![1, 2, 3, 4, 5, 6]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn rows_are_mutable() -> Result<(), Error> {
+        let mut dm = DataMatrix::from_vec(vec![0.0; 4], labels("r", 2), labels("c", 2))?;
+        dm.row_mut(1)[0] = 2.5;
+        dm.as_mut_slice()[1] = 1.5;
+        assert_eq!(dm.get(1, 0), Some(2.5));
+        assert_eq!(dm.get(0, 1), Some(1.5));
+
+        Ok(())
+    }
+
+    #[test]
+    fn shape_mismatch() {
+        let result = DataMatrix::from_vec(vec![1.0; 5], labels("r", 2), labels("c", 3));
+        assert!(matches!(result, Err(Error::IncorrectDataLength { nrows: 2, ncols: 3, n_data: 5, .. })));
+        let result = DataMatrix::new(vec![vec![1.0, 2.0], vec![3.0]], labels("r", 2), labels("c", 2));
+        assert!(matches!(result, Err(Error::IncorrectMatrixLabels { expected: 2, actual: 1 })));
+    }
+
+    #[test]
+    fn loaded_matrix_is_contiguous() -> Result<(), Error> {
+        let dm = DataMatrixBuilder::new()
+            .skip_header(true)
+            .index_columns(3, 4)
+            .symmetric(true)
+            .from_file("./tests/test_files/cities_by_distance.csv.gz")?;
+        assert_eq!(dm.as_slice().len(), dm.nrows() * dm.ncols());
+        for i in 0..dm.nrows() {
+            for j in 0..dm.ncols() {
+                assert_eq!(dm.as_slice()[i * dm.ncols() + j], dm.row(i)[j]);
+                assert_eq!(dm.row(i)[j], dm.row(j)[i]);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+#[cfg(test)]
+mod test_streaming {
+    use data_matrix::{DataMatrixBuilder, Error};
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::fmt::Write as _;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    /// Allocator that records the peak number of bytes allocated at once.
+    struct CountingAllocator;
+
+    static CURRENT: AtomicUsize = AtomicUsize::new(0);
+    static PEAK: AtomicUsize = AtomicUsize::new(0);
+    /// Tests of this file are run one at a time, so they don't disturb each other's measurements
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                let current = CURRENT.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+                PEAK.fetch_max(current, Ordering::SeqCst);
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            CURRENT.fetch_sub(layout.size(), Ordering::SeqCst);
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    /// Three-column text with the upper triangle of an `n x n` symmetric matrix.
+    fn upper_triangle_text(n: usize) -> String {
+        let mut text = String::new();
+        for i in 0..n {
+            for j in i..n {
+                writeln!(text, "taxon_{i} taxon_{j} {}", (i * n + j) as f64 / 7.0).unwrap();
+            }
+        }
+        text
+    }
+
+    #[test]
+    fn peak_memory_of_large_file() -> Result<(), Error> {
+        let _guard = SERIAL.lock().unwrap();
+        let n = 500;
+        let fname = std::env::temp_dir().join(format!("datamatrix_test_streaming_{}.txt", std::process::id()));
+        std::fs::write(&fname, upper_triangle_text(n))?;
+
+        PEAK.store(CURRENT.load(Ordering::SeqCst), Ordering::SeqCst);
+        let before = CURRENT.load(Ordering::SeqCst);
+        let dm = DataMatrixBuilder::new().symmetric(true).from_file(&fname)?;
+        let peak = PEAK.load(Ordering::SeqCst) - before;
+        std::fs::remove_file(&fname)?;
+
+        assert_eq!(dm.nrows(), n);
+        assert_eq!(dm.count_missing(), 0);
+        // --- the input holds over 125 000 lines; buffering them all would take several times more
+        let values_size = n * n * std::mem::size_of::<f64>();
+        assert!(peak < 3 * values_size, "peak memory: {peak} bytes");
+
+        Ok(())
+    }
+
+    #[test]
+    fn peak_memory_of_sparse_input() -> Result<(), Error> {
+        let _guard = SERIAL.lock().unwrap();
+        // --- a chain of 5000 labels: the dense matrix would take 200 MB
+        let n = 5000;
+        let mut text = String::new();
+        for i in 1..n {
+            writeln!(text, "p{} p{i} 1.0", i - 1).unwrap();
+        }
+
+        PEAK.store(CURRENT.load(Ordering::SeqCst), Ordering::SeqCst);
+        let before = CURRENT.load(Ordering::SeqCst);
+        let sparse = DataMatrixBuilder::new().symmetric(true).from_reader_sparse(text.as_bytes())?;
+        let peak = PEAK.load(Ordering::SeqCst) - before;
+
+        assert_eq!(sparse.shape(), (n, n));
+        assert_eq!(sparse.nnz(), 2 * (n - 1));
+        assert!(peak < 2_000_000, "peak memory: {peak} bytes");
+
+        Ok(())
+    }
+
+    #[test]
+    fn streamed_entries() -> Result<(), Error> {
+        let _guard = SERIAL.lock().unwrap();
+        let n = 40;
+        let dm = DataMatrixBuilder::new().symmetric(true).from_str(&upper_triangle_text(n))?;
+        assert_eq!(dm.nrows(), n);
+        for i in 0..n {
+            for j in 0..n {
+                let (lo, hi) = (i.min(j), i.max(j));
+                assert_eq!(dm.get(i, j), Some((lo * n + hi) as f64 / 7.0));
+            }
+        }
+        assert_eq!(dm.row_label(n - 1), &format!("taxon_{}", n - 1));
+
+        Ok(())
+    }
+}
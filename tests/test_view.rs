@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod test_view {
+    use data_matrix::{DataMatrix, DataMatrixBuilder, Error};
+
+    fn labels(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn grid() -> Result<DataMatrix<i32>, Error> {
+        DataMatrix::from_vec((0..12).collect(), labels(&["r1", "r2", "r3"]), labels(&["c1", "c2", "c3", "c4"]))
+    }
+
+    #[test]
+    fn full_view() -> Result<(), Error> {
+        let dm = grid()?;
+        let view = dm.view();
+        assert_eq!(view.shape(), dm.shape());
+        assert_eq!(view.row_labels(), dm.row_labels());
+        assert_eq!(view.get(2, 1), Some(9));
+        assert_eq!(view.get(3, 0), None);
+        assert_eq!(view.get_by_label("r2", "c4"), Some(7));
+        assert_eq!(view.row(1).copied().collect::<Vec<_>>(), dm.row(1));
+        assert_eq!(view.rows().count(), 3);
+        assert_eq!(view.iter().map(|(_, _, &v)| v).collect::<Vec<_>>(), dm.as_slice());
+        assert_eq!(view.to_owned().as_slice(), dm.as_slice());
+
+        Ok(())
+    }
+
+    #[test]
+    fn select_and_transpose() -> Result<(), Error> {
+        let dm = grid()?;
+        let view = dm.view().select(&["r3", "r1"], &["c2", "c4", "c1"])?;
+        assert_eq!(view.shape(), (2, 3));
+        assert_eq!(view.row_labels(), ["r3", "r1"]);
+        assert_eq!(view.row(0).copied().collect::<Vec<_>>(), [9, 11, 8]);
+        assert_eq!(view.row_index("r1"), Some(1));
+        assert_eq!(view.row_index("r2"), None);
+        assert_eq!(view.get_by_label("r2", "c1"), None);
+
+        let transposed = view.clone().transpose();
+        assert_eq!(transposed.shape(), (3, 2));
+        assert_eq!(transposed.row_labels(), ["c2", "c4", "c1"]);
+        assert_eq!(transposed.col_label(0), "r3");
+        assert_eq!(transposed.get_by_label("c4", "r1"), Some(3));
+        assert_eq!(transposed.col_index("c4"), None);
+
+        // --- a selection of a transposed view picks its rows, i.e. columns of the matrix
+        let nested = transposed.select_indices(&[2, 0], &[1])?;
+        assert_eq!(nested.row_labels(), ["c1", "c2"]);
+        assert_eq!(nested.col_labels(), ["r1"]);
+        let copy = nested.to_owned();
+        assert_eq!(copy.as_slice(), &[0, 1]);
+        assert_eq!(copy.row_index("c2"), Some(1));
+        assert_eq!(copy.col_labels(), ["r1"]);
+
+        assert!(matches!(transposed.select(&["r1"], &["r1"]), Err(Error::UnknownLabel { label }) if label == "r1"));
+        assert!(matches!(transposed.select_indices(&[3], &[0]), Err(Error::IndexOutOfBounds { row: 3, nrows: 3, ncols: 2, .. })));
+        assert!(matches!(view.select_indices(&[0, 0], &[0]), Err(Error::DuplicateLabel { label }) if label == "r3"));
+        assert!(matches!(view.select(&["r1"], &[]), Err(Error::EmptySelection { .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn missing_entries() -> Result<(), Error> {
+        let dm = DataMatrixBuilder::new().from_str("a x 1.0\na y 2.0\nb y 3.0\n")?;
+        let view = dm.view().transpose();
+        assert_eq!(view.count_missing(), 1);
+        assert!(view.is_missing(0, 1) && !view.is_missing(1, 1));
+        assert!(!view.is_missing(5, 5));
+        let copy = view.to_owned();
+        assert_eq!(copy.count_missing(), 1);
+        assert!(copy.is_missing(0, 1));
+        assert_eq!(copy.row_labels(), ["x", "y"]);
+
+        Ok(())
+    }
+}
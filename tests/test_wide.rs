@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod test_wide {
+    use data_matrix::{DataMatrixBuilder, DataMatrixWriter, Error, Layout};
+
+    #[test]
+    fn from_wide_csv() -> Result<(), Error> {
+        let dm = DataMatrixBuilder::new().corner_label(true).from_wide_file("./tests/test_files/wide_short.csv")?;
+        assert_eq!(dm.row_labels(), ["G1", "G2"]);
+        assert_eq!(dm.col_labels(), ["S1", "S2", "S3"]);
+        assert_eq!(dm.get_by_label("G1", "S2"), Some(0.93));
+
+        Ok(())
+    }
+
+    #[test]
+    fn spaces_after_separators() -> Result<(), Error> {
+        for (text, corner) in [("id, A, B\nr1, 1.0, 2.0\nr2 ,3.0 , 4.0\n", true), (" A , B\nr1, 1.0, 2.0\nr2, 3.0, 4.0\n", false)] {
+            let dm = DataMatrixBuilder::new().separator(',').corner_label(corner).from_wide_reader(text.as_bytes())?;
+            assert_eq!(dm.row_labels(), ["r1", "r2"]);
+            assert_eq!(dm.col_labels(), ["A", "B"]);
+            assert_eq!(dm.get_by_label("r2", "B"), Some(4.0));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn wide_round_trip() -> Result<(), Error> {
+        let dm = DataMatrixBuilder::new()
+            .symmetric(true)
+            .data_column(2)
+            .skip_header(true)
+            .index_columns(3, 4)
+            .from_file("./tests/test_files/cities_by_distance.csv.gz")?;
+        for name in ["wide.tsv", "wide.csv.gz"] {
+            let output = std::env::temp_dir().join(format!("datamatrix_test_{}_{}", std::process::id(), name));
+            DataMatrixWriter::new().layout(Layout::Wide).to_file(&dm, &output)?;
+            let copy = DataMatrixBuilder::new().from_wide_file(&output)?;
+            assert_eq!(dm.row_labels(), copy.row_labels());
+            assert_eq!(dm.col_labels(), copy.col_labels());
+            assert_eq!(dm.to_rows(), copy.to_rows());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn wrong_number_of_values() {
+        let output = std::env::temp_dir().join(format!("datamatrix_test_wide_ragged_{}.txt", std::process::id()));
+        std::fs::write(&output, "# ragged table\nA B\nA 1 2\n\nB 3\nC 4 5 6\n").unwrap();
+        let result = DataMatrixBuilder::new().from_wide_file(&output);
+        assert!(matches!(result, Err(Error::NotEnoughColumns { line: 5, needed: 3, .. })));
+
+        std::fs::write(&output, "A B\nA 1 2\nC 4 5 6\n").unwrap();
+        let result = DataMatrixBuilder::new().from_wide_file(&output);
+        assert!(matches!(result, Err(Error::TooManyColumns { line: 3, .. })));
+    }
+
+    #[test]
+    fn short_first_row() {
+        // --- the first row is checked against the header, like any other row
+        let result = DataMatrixBuilder::new().separator(',').from_wide_reader("A,B,C\nr1,1,2\nr2,3,4\n".as_bytes());
+        assert!(matches!(result, Err(Error::NotEnoughColumns { line: 2, needed: 4, .. })));
+
+        let result = DataMatrixBuilder::new()
+            .separator(',')
+            .corner_label(true)
+            .from_wide_reader("id,A,B,C\nr1,1,2\nr2,3,4\n".as_bytes());
+        assert!(matches!(result, Err(Error::NotEnoughColumns { line: 2, needed: 4, .. })));
+
+        let result = DataMatrixBuilder::new().separator(',').from_wide_reader(",A,B,C\nr1,1,2\n".as_bytes());
+        assert!(matches!(result, Err(Error::NotEnoughColumns { line: 2, needed: 4, .. })));
+    }
+}
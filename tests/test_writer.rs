@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod test_writer {
+    use data_matrix::{DataMatrix, DataMatrixBuilder, DataMatrixWriter, Error, Layout};
+    use std::path::PathBuf;
+
+    fn cities() -> Result<DataMatrix, Error> {
+        DataMatrixBuilder::new()
+            .symmetric(true)
+            .data_column(2)
+            .skip_header(true)
+            .index_columns(3, 4)
+            .from_file("./tests/test_files/cities_by_distance.csv")
+    }
+
+    fn output_file(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("datamatrix_test_writer_{}_{}", std::process::id(), name))
+    }
+
+    fn assert_same(expected: &DataMatrix, actual: &DataMatrix) {
+        assert_eq!(expected.row_labels(), actual.row_labels());
+        assert_eq!(expected.col_labels(), actual.col_labels());
+        assert_eq!(expected.to_rows(), actual.to_rows());
+    }
+
+    #[test]
+    fn three_columns_round_trip() -> Result<(), Error> {
+        let dm = cities()?;
+        for (name, upper) in [("three.csv", false), ("three_upper.csv.gz", true)] {
+            let output = output_file(name);
+            DataMatrixWriter::new()
+                .header(true)
+                .comment("cities\nby distance")
+                .upper_triangle(upper)
+                .to_file(&dm, &output)?;
+            let copy = DataMatrixBuilder::new()
+                .symmetric(true)
+                .skip_header(true)
+                .from_file(&output)?;
+            assert_same(&dm, &copy);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn five_columns_round_trip() -> Result<(), Error> {
+        let dm = cities()?;
+        let output = output_file("five.tsv");
+        DataMatrixWriter::new()
+            .layout(Layout::FiveColumns)
+            .upper_triangle(true)
+            .to_file(&dm, &output)?;
+        let copy = DataMatrixBuilder::new()
+            .symmetric(true)
+            .index_columns(2, 3)
+            .data_column(4)
+            .from_file(&output)?;
+        assert_same(&dm, &copy);
+
+        Ok(())
+    }
+
+    #[test]
+    fn single_column_round_trip() -> Result<(), Error> {
+        let dm = cities()?;
+        let output = output_file("single.txt");
+        DataMatrixWriter::new()
+            .layout(Layout::SingleColumn)
+            .to_file(&dm, &output)?;
+        let copy = DataMatrixBuilder::new()
+            .labels(dm.row_labels().to_vec())
+            .data_column(0)
+            .from_file(&output)?;
+        assert_same(&dm, &copy);
+
+        Ok(())
+    }
+
+    #[test]
+    fn header_and_comments() -> Result<(), Error> {
+        let dm = DataMatrixBuilder::new().labels(["A", "B"]).from_data(&[1.0, 2.5, 2.5, 4.0])?;
+        let mut buffer: Vec<u8> = Vec::new();
+        DataMatrixWriter::new()
+            .separator('\t')
+            .header(true)
+            .comment("two labels")
+            .upper_triangle(true)
+            .to_writer(&dm, &mut buffer)?;
+        let text = String::from_utf8(buffer).unwrap();
+        assert_eq!(text, "# two labels\nrow_label\tcol_label\tvalue\nA\tA\t1\nA\tB\t2.5\nB\tB\t4\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn upper_triangle_needs_square_matrix() {
+        let data = vec![vec![1.0, 2.0, 3.0]];
+        let dm = DataMatrix::new(data, vec!["r".into()], vec!["a".into(), "b".into(), "c".into()]).unwrap();
+        let result = DataMatrixWriter::new()
+            .upper_triangle(true)
+            .to_writer(&dm, std::io::sink());
+        assert!(matches!(result, Err(Error::NotSquare { nrows: 1, ncols: 3 })));
+    }
+}